@@ -12,6 +12,13 @@ const svgContent = `<svg width="512" height="512" viewBox="0 0 512 512" fill="no
   <path d="M200 200L280 256L200 312" stroke="#ff4136" stroke-width="40" stroke-linecap="round" stroke-linejoin="round"/>
 </svg>`;
 
+// Tray icon shown while a guarded or watched port is occupied: the normal
+// icon with a red badge.
+const alertSvgContent = svgContent.replace(
+  '</svg>',
+  '  <circle cx="400" cy="112" r="100" fill="#ff4136"/>\n</svg>'
+);
+
 const iconsDir = path.join(__dirname, '..', 'src-tauri', 'icons');
 
 if (!fs.existsSync(iconsDir)) {
@@ -53,6 +60,13 @@ async function generateIcons() {
     }
   }
 
+  const alertBuffer = await sharp(Buffer.from(alertSvgContent))
+    .resize(128, 128)
+    .png()
+    .toBuffer();
+  await fs.promises.writeFile(path.join(iconsDir, 'tray-alert.png'), alertBuffer);
+  console.log('Generated tray-alert.png');
+
   const icoBuffer = await toIco(pngBuffers.slice(0, 4));
   await fs.promises.writeFile(path.join(iconsDir, 'icon.ico'), icoBuffer);
   console.log('Generated icon.ico');
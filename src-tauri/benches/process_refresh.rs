@@ -0,0 +1,44 @@
+// Full process refresh vs. refreshing only the PIDs that own sockets, the
+// choice collect_ports makes on every poll. Run with `cargo bench`.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use netstat2::{get_sockets_info, AddressFamilyFlags, ProtocolFlags};
+use std::collections::HashSet;
+use sysinfo::{Pid, ProcessesToUpdate, System};
+
+fn socket_owners() -> Vec<Pid> {
+    let sockets = get_sockets_info(
+        AddressFamilyFlags::IPV4 | AddressFamilyFlags::IPV6,
+        ProtocolFlags::TCP | ProtocolFlags::UDP,
+    )
+    .expect("socket enumeration failed");
+    let owners: HashSet<u32> = sockets
+        .iter()
+        .flat_map(|s| s.associated_pids.iter().copied())
+        .collect();
+    owners.into_iter().map(Pid::from_u32).collect()
+}
+
+fn process_refresh(c: &mut Criterion) {
+    let owners = socket_owners();
+    // Warm instance, like the long-lived one in AppData.
+    let mut system = System::new();
+    system.refresh_processes(ProcessesToUpdate::All);
+    println!(
+        "{} processes, {} socket owners",
+        system.processes().len(),
+        owners.len()
+    );
+
+    let mut group = c.benchmark_group("process_refresh");
+    group.bench_function("all", |b| {
+        b.iter(|| system.refresh_processes(ProcessesToUpdate::All))
+    });
+    group.bench_function("socket_owners", |b| {
+        b.iter(|| system.refresh_processes(ProcessesToUpdate::Some(&owners)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, process_refresh);
+criterion_main!(benches);
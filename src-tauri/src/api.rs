@@ -0,0 +1,130 @@
+// Optional localhost HTTP API for scripting PortKiller from a terminal.
+// Enabled with `--api-port <port>`; every request must carry the token that
+// is written to API_TOKEN_FILE in the app config dir on startup. Release
+// Windows builds have no console, so stdout isn't an option.
+
+use crate::{
+    cli_arg, collect_ports, config_file, emit_kill_results, kill_pid, listening_states, AppData,
+    KillOptions, ScanOptions,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::fs;
+use std::hash::{BuildHasher, Hasher};
+use std::io::Read;
+use tauri::{AppHandle, Manager};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+const TOKEN_HEADER: &str = "X-PortKiller-Token";
+const API_TOKEN_FILE: &str = "api-token";
+
+#[derive(Deserialize)]
+struct KillRequest {
+    pid: u32,
+    port: u16,
+    process_name: String,
+    #[serde(default)]
+    options: KillOptions,
+}
+
+// `--api-port 17000` or `--api-port=17000`.
+pub fn port_from_args() -> Option<u16> {
+    cli_arg("--api-port")?.parse().ok()
+}
+
+// RandomState keys come from the OS RNG, which is plenty for a per-launch
+// localhost token and saves pulling in a rand crate.
+fn generate_token() -> String {
+    (0..2)
+        .map(|_| format!("{:016x}", RandomState::new().build_hasher().finish()))
+        .collect()
+}
+
+// Compares every byte whatever the first mismatch, so response timing
+// doesn't reveal how much of a guess was right. The length isn't secret.
+fn tokens_match(given: &str, token: &str) -> bool {
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+// Bind to 127.0.0.1 only and serve requests on a background thread.
+pub fn start(app: AppHandle, port: u16) -> Result<(), String> {
+    let server = Server::http(("127.0.0.1", port)).map_err(|e| e.to_string())?;
+    let token = generate_token();
+    let path = config_file(&app, API_TOKEN_FILE)?;
+    fs::write(&path, &token).map_err(|e| e.to_string())?;
+    app.state::<AppData>().log(
+        "info",
+        format!(
+            "API listening on http://127.0.0.1:{}; send the {} header with the token in {}",
+            port,
+            TOKEN_HEADER,
+            path.display()
+        ),
+    );
+
+    std::thread::spawn(move || {
+        for mut request in server.incoming_requests() {
+            let (status, body) = handle(&app, &token, &mut request);
+            let content_type = Header::from_bytes("Content-Type", "application/json")
+                .expect("static header is valid");
+            let response = Response::from_string(body)
+                .with_status_code(status)
+                .with_header(content_type);
+            let _ = request.respond(response);
+        }
+    });
+    Ok(())
+}
+
+fn handle(app: &AppHandle, token: &str, request: &mut Request) -> (u16, String) {
+    let authorized = request
+        .headers()
+        .iter()
+        .any(|h| h.field.equiv(TOKEN_HEADER) && tokens_match(h.value.as_str(), token));
+    if !authorized {
+        return error(401, "missing or invalid token");
+    }
+
+    let data = app.state::<AppData>();
+    let method = request.method().clone();
+    let url = request.url().to_string();
+    match (method, url.as_str()) {
+        (Method::Get, "/ports") => {
+            match collect_ports(&data, &listening_states(), &ScanOptions::default()) {
+                Ok(state) => json(200, &state),
+                Err(e) => error(500, &e.to_string()),
+            }
+        }
+        (Method::Post, "/kill") => {
+            let mut body = String::new();
+            if let Err(e) = request.as_reader().read_to_string(&mut body) {
+                return error(400, &e.to_string());
+            }
+            match serde_json::from_str::<KillRequest>(&body) {
+                Ok(req) => {
+                    let result = kill_pid(&data, req.pid, req.port, req.process_name, &req.options);
+                    emit_kill_results(app, std::slice::from_ref(&result));
+                    json(200, &result)
+                }
+                Err(e) => error(400, &e.to_string()),
+            }
+        }
+        _ => error(404, "not found"),
+    }
+}
+
+fn json<T: Serialize>(status: u16, value: &T) -> (u16, String) {
+    match serde_json::to_string(value) {
+        Ok(body) => (status, body),
+        Err(e) => error(500, &e.to_string()),
+    }
+}
+
+fn error(status: u16, message: &str) -> (u16, String) {
+    (status, serde_json::json!({ "error": message }).to_string())
+}
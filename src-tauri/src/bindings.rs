@@ -0,0 +1,45 @@
+// `--emit-bindings <dir>` (built with `--features bindings`) writes the
+// TypeScript declaration of every command input and output into `dir`, one
+// file per type, and exits. src/types.ts is kept in step with its output.
+//
+// ScanOptions and KillOptions are `#[serde(default)]`: the declarations list
+// every field, but callers may omit any of them.
+
+use crate::error::AppErrorShape;
+use crate::platform::SignatureInfo;
+use crate::{
+    AppState, ConnectionStats, GuardTriggered, KillOptions, KillRecord, KillResult, LogEntry,
+    PortDiagnosis, PortDiff, PortInfo, ProbeResult, ProcessDetails, ProcessGroup, ScanOptions,
+    Settings, SystemStats, ViewState,
+};
+use std::path::Path;
+use ts_rs::{ExportError, TS};
+
+pub const EMIT_BINDINGS_ARG: &str = "--emit-bindings";
+
+// export_all_to also writes every type these reference (PortSocket,
+// ConnectionInfo, ...), so only the top-level ones are listed.
+pub fn emit(dir: &Path) -> Result<(), ExportError> {
+    // Command inputs.
+    ScanOptions::export_all_to(dir)?;
+    KillOptions::export_all_to(dir)?;
+    Settings::export_all_to(dir)?;
+    ViewState::export_all_to(dir)?;
+    // Command results and event payloads.
+    AppState::export_all_to(dir)?;
+    KillResult::export_all_to(dir)?;
+    AppErrorShape::export_all_to(dir)?;
+    ProcessGroup::export_all_to(dir)?;
+    ProcessDetails::export_all_to(dir)?;
+    SignatureInfo::export_all_to(dir)?;
+    ConnectionStats::export_all_to(dir)?;
+    SystemStats::export_all_to(dir)?;
+    KillRecord::export_all_to(dir)?;
+    LogEntry::export_all_to(dir)?;
+    ProbeResult::export_all_to(dir)?;
+    PortDiagnosis::export_all_to(dir)?;
+    PortDiff::export_all_to(dir)?;
+    GuardTriggered::export_all_to(dir)?;
+    PortInfo::export_all_to(dir)?;
+    Ok(())
+}
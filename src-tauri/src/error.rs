@@ -0,0 +1,101 @@
+// Structured command error. Serialized as `{ "kind": "AccessDenied",
+// "message": "..." }` so the frontend can branch on `kind` (e.g. offer to
+// restart elevated) and still show `message` verbatim.
+
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppError {
+    // Needs elevation; the UI offers "Restart as Administrator".
+    AccessDenied(String),
+    // The process, socket or file is gone.
+    NotFound(String),
+    // Refused because the target is on the protected list.
+    Protected(String),
+    // Bad argument from the caller (unknown protocol, inverted range, ...).
+    InvalidInput(String),
+    Io(String),
+    Other(String),
+}
+
+impl AppError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AppError::AccessDenied(_) => "AccessDenied",
+            AppError::NotFound(_) => "NotFound",
+            AppError::Protected(_) => "Protected",
+            AppError::InvalidInput(_) => "InvalidInput",
+            AppError::Io(_) => "Io",
+            AppError::Other(_) => "Other",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::AccessDenied(m)
+            | AppError::NotFound(m)
+            | AppError::Protected(m)
+            | AppError::InvalidInput(m)
+            | AppError::Io(m)
+            | AppError::Other(m) => m,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 2)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", self.message())?;
+        state.end()
+    }
+}
+
+// Helpers that still report plain strings convert with `?`; they carry no
+// classification, so they land in Other.
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::Other(message)
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(error: std::io::Error) -> Self {
+        match error.kind() {
+            std::io::ErrorKind::PermissionDenied => AppError::AccessDenied(error.to_string()),
+            std::io::ErrorKind::NotFound => AppError::NotFound(error.to_string()),
+            _ => AppError::Io(error.to_string()),
+        }
+    }
+}
+
+// The `{ kind, message }` shape the Serialize impl above produces, for the
+// generated TypeScript bindings; ts-rs can't see through a hand-written impl.
+// Only ever described, never built.
+#[cfg(feature = "bindings")]
+#[allow(dead_code)]
+#[derive(ts_rs::TS)]
+#[ts(rename = "AppError")]
+pub struct AppErrorShape {
+    pub kind: AppErrorKind,
+    pub message: String,
+}
+
+#[cfg(feature = "bindings")]
+#[allow(dead_code)]
+#[derive(ts_rs::TS)]
+pub enum AppErrorKind {
+    AccessDenied,
+    NotFound,
+    Protected,
+    InvalidInput,
+    Io,
+    Other,
+}
@@ -3,34 +3,161 @@
     windows_subsystem = "windows"
 )]
 
+mod api;
+#[cfg(feature = "bindings")]
+mod bindings;
+mod error;
+mod platform;
+mod rpc;
+
+use error::AppError;
 use netstat2::{get_sockets_info, AddressFamilyFlags, ProtocolFlags, ProtocolSocketInfo};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
-use std::os::windows::process::CommandExt;
-use std::sync::Mutex;
-use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, RefreshKind, System};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::ffi::OsString;
+use std::fs;
+use std::io::Write;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, TcpListener};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime};
+use sysinfo::{
+    Pid, ProcessRefreshKind, ProcessesToUpdate, RefreshKind, System, MINIMUM_CPU_UPDATE_INTERVAL,
+};
 use tauri::{
-    menu::{Menu, MenuItem},
+    menu::{Menu, MenuItem, PredefinedMenuItem},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    AppHandle, Manager, State, WebviewWindow,
+    webview::PageLoadEvent,
+    AppHandle, Emitter, Manager, State, WebviewWindow, WindowEvent, Wry,
 };
+use tauri_plugin_clipboard_manager::ClipboardExt;
 use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
-use windows::core::PCWSTR;
-use windows::Win32::Foundation::{CloseHandle, GetLastError, ERROR_ALREADY_EXISTS, HANDLE};
-use windows::Win32::Security::{GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY};
-use windows::Win32::System::Threading::{
-    CreateMutexW, GetCurrentProcess, OpenProcess, OpenProcessToken, TerminateProcess,
-    PROCESS_TERMINATE,
-};
+use tauri_plugin_notification::NotificationExt;
+#[cfg(feature = "bindings")]
+use ts_rs::TS;
 
 // Reusable sysinfo instance — creating a fresh System on every poll is the
 // single biggest CPU cost in the old code path.
 struct AppData {
     system: Mutex<System>,
     is_admin: bool,
+    // Lowercased process names protected from termination: the built-in
+    // PROTECTED_PROCESSES plus anything the user added at runtime. Only the
+    // additions are matched by name; see AppData::is_protected.
+    protected: Mutex<HashSet<String>>,
+    // Lowercased process names hidden from listings. Unlike `protected` this
+    // doesn't stop kills, it only cuts noise.
+    ignored: Mutex<HashSet<String>>,
+    // Bumped on every start/stop so a superseded watcher thread notices and
+    // exits even if a new one was started before it woke up.
+    watch_generation: AtomicU64,
+    watch_active: AtomicBool,
+    // Watched port -> PIDs we've already alerted about on it.
+    watched_ports: Mutex<HashMap<u16, HashSet<u32>>>,
+    // Guarded port -> PIDs we've already tried to kill on it.
+    guarded_ports: Mutex<HashMap<u16, HashSet<u32>>>,
+    // Lowercased process names (or globs) whose rows sort to the top.
+    favorites: Mutex<HashSet<String>>,
+    // Ports the background refresh narrows to; empty means every port.
+    watchlist: Mutex<Vec<u16>>,
+    // Ports we're holding ourselves with a placeholder listener.
+    reserved_ports: Mutex<HashMap<u16, PortReservation>>,
+    // Reverse-DNS results keyed by remote IP. `None` records a failed or
+    // timed-out lookup so we don't retry it on every refresh.
+    dns_cache: Mutex<HashMap<IpAddr, Option<String>>>,
+    // PIDs we've suspended and not yet resumed. A suspended process still
+    // holds its ports, so the details panel needs to say so.
+    suspended: Mutex<HashSet<u32>>,
+    // Audit trail of kill attempts, newest last, mirrored to `history_path`.
+    kill_history: Mutex<Vec<KillRecord>>,
+    history_path: OnceLock<PathBuf>,
+    // Currently registered show/hide hotkey.
+    hotkey: Mutex<HotkeyBinding>,
+    // Signature checks keyed on (executable, modification time).
+    signatures: Mutex<HashMap<SignatureKey, Option<platform::SignatureInfo>>>,
+    // Offline GeoLite2 Country database, if the user has supplied one.
+    geoip: Mutex<Option<maxminddb::Reader<Vec<u8>>>>,
+    // User preferences, mirrored to settings.json.
+    settings: Mutex<Settings>,
+    // Whether we've told the user this session that closing the window
+    // only hid it.
+    close_notice_shown: AtomicBool,
+    // Recent operations and failures for the log viewer, oldest first,
+    // capped at LOG_CAPACITY.
+    logs: Mutex<VecDeque<LogEntry>>,
+    // View handed over by restart_as_admin, until the frontend claims it.
+    restore_view: Mutex<Option<ViewState>>,
+}
+
+type SignatureKey = (PathBuf, Option<SystemTime>);
+
+struct HotkeyBinding {
+    shortcut: Shortcut,
+    accelerator: String,
+}
+
+// Tray menu entries whose label changes at runtime.
+struct TrayMenuItems {
+    show: MenuItem<Wry>,
+    // (port, process name) of the "Free ..." entries currently shown, so
+    // refreshes that change nothing don't rebuild the menu.
+    dev_ports: Mutex<Vec<(u16, String)>>,
+    // Guarded / watched ports currently held, behind the alert icon.
+    alert_ports: Mutex<Vec<u16>>,
+}
+
+impl AppData {
+    // Built-ins in the set are skipped: they go through the path-verified
+    // is_protected_process, so a name-only match here would undo that.
+    fn is_protected(&self, pid: u32, name: &str) -> bool {
+        if is_protected_process(pid, name) {
+            return true;
+        }
+        self.protected
+            .lock()
+            .map(|set| {
+                set.iter()
+                    .filter(|pattern| !PROTECTED_PROCESSES.contains(&pattern.as_str()))
+                    .any(|pattern| name_matches(pattern, name))
+            })
+            .unwrap_or(true)
+    }
+
+    fn is_ignored(&self, name: &str) -> bool {
+        self.ignored
+            .lock()
+            .map(|set| set.iter().any(|pattern| name_matches(pattern, name)))
+            .unwrap_or(false)
+    }
+
+    fn is_favorite(&self, name: &str) -> bool {
+        self.favorites
+            .lock()
+            .map(|set| set.iter().any(|pattern| name_matches(pattern, name)))
+            .unwrap_or(false)
+    }
+
+    // Record an entry for the log viewer and echo it to stderr.
+    fn log(&self, level: &str, message: impl Into<String>) {
+        let message = message.into();
+        eprintln!("[{}] {}", level, message);
+        let Ok(mut logs) = self.logs.lock() else {
+            return;
+        };
+        if logs.len() == LOG_CAPACITY {
+            logs.pop_front();
+        }
+        logs.push_back(LogEntry {
+            timestamp: unix_now(),
+            level: level.to_string(),
+            message,
+        });
+    }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "bindings", derive(TS))]
 pub struct PortInfo {
     pub pid: u32,
     pub port: u16,
@@ -39,30 +166,225 @@ pub struct PortInfo {
     pub process_path: String,
     pub is_protected: bool,
     pub local_address: String,
+    // TCP state name as reported by netstat2 ("Listen", "Established", ...).
+    // UDP has no state and is tagged "None".
+    pub state: String,
+    // IANA / common dev-tool name for the port ("postgresql", "redis", ...).
+    pub service_hint: Option<String>,
+    // Peer endpoint for connected TCP sockets; None for listeners and UDP.
+    pub remote_address: Option<String>,
+    // Reverse-DNS name of the peer, only when the caller opted in.
+    pub remote_host: Option<String>,
+    // ISO country code of a public peer, when a GeoIP database is loaded.
+    pub remote_country: Option<String>,
+    // Listener is a Docker / WSL port forwarder rather than the real server;
+    // killing it tears down the forwarding, not the container or distro.
+    pub is_proxied: bool,
+    // "docker" or "wsl" when `is_proxied`.
+    pub proxy_kind: Option<String>,
+    // TCP: the socket is in Listen. UDP has no listen state, so this is the
+    // is_likely_udp_server guess.
+    pub likely_server: bool,
+    // Who can reach the bind: "local", "lan" or "public". See `exposure`.
+    pub exposure: String,
+    // Owner's start time (Unix seconds), 0 if unknown. A new value on the
+    // same port means the listener restarted, even if the PID got reused.
+    #[cfg_attr(feature = "bindings", ts(type = "number"))]
+    pub process_start_time: u64,
+    // TCP bytes waiting to be read by the owner / acknowledged by the peer,
+    // when asked for and the platform exposes them. On a listener
+    // `recv_queue` is the accept backlog, in connections: one that keeps
+    // growing is accepting too slowly.
+    pub recv_queue: Option<u32>,
+    pub send_queue: Option<u32>,
+    // Owner is on the user's favorites list; its rows sort first.
+    pub is_favorite: bool,
 }
 
 #[derive(Serialize, Clone)]
+#[cfg_attr(feature = "bindings", derive(TS))]
 pub struct AppState {
     pub ports: Vec<PortInfo>,
+    #[cfg_attr(feature = "bindings", ts(type = "number"))]
     pub last_updated: u64,
     pub is_admin: bool,
+    // Row counts by base protocol ("TCP6" counts as TCP), for the header.
+    pub tcp_count: usize,
+    pub udp_count: usize,
+    pub total: usize,
+}
+
+impl AppState {
+    fn new(ports: Vec<PortInfo>, last_updated: u64, is_admin: bool) -> Self {
+        let mut state = AppState {
+            ports,
+            last_updated,
+            is_admin,
+            tcp_count: 0,
+            udp_count: 0,
+            total: 0,
+        };
+        state.count();
+        state
+    }
+
+    // Drop rows, keeping the counts in step.
+    fn retain_ports(&mut self, keep: impl FnMut(&PortInfo) -> bool) {
+        self.ports.retain(keep);
+        self.count();
+    }
+
+    fn count(&mut self) {
+        let of = |protocol: &str| {
+            self.ports
+                .iter()
+                .filter(|p| base_protocol(&p.protocol) == protocol)
+                .count()
+        };
+        self.tcp_count = of("TCP");
+        self.udp_count = of("UDP");
+        self.total = self.ports.len();
+    }
 }
 
 #[derive(Serialize, Clone)]
+#[cfg_attr(feature = "bindings", derive(TS))]
 pub struct KillResult {
     pub success: bool,
     pub message: String,
     pub port: u16,
+    // Set when the target was classified High risk and the caller must retry
+    // with `confirmed: true`.
+    pub needs_confirmation: bool,
+    // Classified failure behind `message`, so the UI can react to e.g.
+    // AccessDenied without string matching. None on success.
+    #[cfg_attr(feature = "bindings", ts(as = "Option<error::AppErrorShape>"))]
+    pub error: Option<AppError>,
+    // How the process was terminated: "api" or "taskkill". Empty when
+    // nothing was killed (protected, dry run, graceful exit, ...).
+    pub method: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum KillRisk {
+    Low,
+    Medium,
+    High,
+}
+
+// All listening rows of one process, for the tree view.
+#[derive(Serialize, Clone)]
+#[cfg_attr(feature = "bindings", derive(TS))]
+pub struct ProcessGroup {
+    pub pid: u32,
+    pub process_name: String,
+    pub process_path: String,
+    pub is_protected: bool,
+    pub ports: Vec<PortInfo>,
+}
+
+#[derive(Serialize, Clone)]
+#[cfg_attr(feature = "bindings", derive(TS))]
+pub struct ConnectionStats {
+    pub total_connections: usize,
+    pub listening_count: usize,
+    pub established_count: usize,
+    // (process name, socket count), busiest first.
+    pub by_process: Vec<(String, u32)>,
+}
+
+#[derive(Serialize, Clone)]
+#[cfg_attr(feature = "bindings", derive(TS))]
+pub struct SystemStats {
+    #[cfg_attr(feature = "bindings", ts(type = "number"))]
+    pub total_memory: u64,
+    #[cfg_attr(feature = "bindings", ts(type = "number"))]
+    pub used_memory: u64,
+    pub cpu_usage_total: f32,
+    pub per_core: Vec<f32>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "bindings", derive(TS))]
+pub struct KillRecord {
+    #[cfg_attr(feature = "bindings", ts(type = "number"))]
+    pub timestamp: u64,
+    pub pid: u32,
+    pub port: u16,
+    pub process_name: String,
+    pub success: bool,
+}
+
+const LOG_CAPACITY: usize = 500;
+
+#[derive(Serialize, Clone)]
+#[cfg_attr(feature = "bindings", derive(TS))]
+pub struct LogEntry {
+    #[cfg_attr(feature = "bindings", ts(type = "number"))]
+    pub timestamp: u64,
+    // "info", "warn" or "error".
+    pub level: String,
+    pub message: String,
+}
+
+// One link of a process ancestry chain.
+#[derive(Serialize, Clone)]
+#[cfg_attr(feature = "bindings", derive(TS))]
+pub struct ProcessDetailsLite {
+    pub pid: u32,
+    pub name: String,
+    pub path: String,
 }
 
 #[derive(Serialize, Clone)]
+#[cfg_attr(feature = "bindings", derive(TS))]
 pub struct ProcessDetails {
     pub pid: u32,
     pub name: String,
     pub path: String,
+    // Resident / working-set size. sysinfo reports 0 when access is denied.
+    #[cfg_attr(feature = "bindings", ts(type = "number"))]
     pub memory_bytes: u64,
+    #[cfg_attr(feature = "bindings", ts(type = "number"))]
+    pub virtual_memory_bytes: u64,
+    // `memory_bytes` formatted for display, e.g. "256.4 MB".
+    pub memory_human: String,
     pub cpu_percent: f32,
     pub children: Vec<u32>,
+    // Full command line, e.g. ["node", "server.js", "--port", "3000"]. Empty
+    // when the OS won't let us read it.
+    pub cmd: Vec<String>,
+    pub is_suspended: bool,
+    pub parent_pid: Option<u32>,
+    // "Unknown" when the parent has exited and the PID is stale.
+    pub parent_name: Option<String>,
+    // Cumulative I/O since the process started.
+    #[cfg_attr(feature = "bindings", ts(type = "number"))]
+    pub disk_read_bytes: u64,
+    #[cfg_attr(feature = "bindings", ts(type = "number"))]
+    pub disk_written_bytes: u64,
+    #[cfg_attr(feature = "bindings", ts(type = "number"))]
+    pub run_duration_secs: u64,
+    // Open kernel handles (Windows) / file descriptors (Linux). None when the
+    // process can't be opened for querying.
+    pub handle_count: Option<u32>,
+    // Ports the process listens on (TCP Listen or bound UDP), ascending.
+    pub listening_ports: Vec<u16>,
+    // Every socket the process holds, listeners included.
+    pub connections: Vec<ConnectionInfo>,
+    // Code-signing status of `path` (Windows). None when there's no path or
+    // the platform has no signatures to check.
+    pub signature: Option<platform::SignatureInfo>,
+}
+
+#[derive(Serialize, Clone)]
+#[cfg_attr(feature = "bindings", derive(TS))]
+pub struct ConnectionInfo {
+    pub protocol: String,
+    pub local_address: String,
+    pub local_port: u16,
+    pub remote_address: Option<String>,
+    pub state: String,
 }
 
 const PROTECTED_PROCESSES: &[&str] = &[
@@ -79,14 +401,173 @@ const PROTECTED_PROCESSES: &[&str] = &[
     "taskmgr.exe",
 ];
 
+// System Idle / System on Windows; the kernel scheduler and init on Unix.
+#[cfg(windows)]
 const PROTECTED_PIDS: &[u32] = &[0, 4];
+#[cfg(unix)]
+const PROTECTED_PIDS: &[u32] = &[0, 1];
+
+// Processes that hold sockets on behalf of other code (service hosts, COM
+// surrogates, init). Never the "real" owner when sharing a socket.
+const SHARED_HOST_PROCESSES: &[&str] = &[
+    "svchost.exe",
+    "services.exe",
+    "dllhost.exe",
+    "system",
+    "systemd",
+    "launchd",
+];
+
+fn is_shared_host(name: &str) -> bool {
+    let name_lower = name.to_lowercase();
+    SHARED_HOST_PROCESSES.iter().any(|&p| name_lower == p)
+}
 
+// Shortest name accepted as a truncated form of a protected name.
+const MIN_PROTECTED_PREFIX: usize = 4;
+
+// Names alone are spoofable: a copy of svchost.exe in a user folder is not the
+// real thing. The name check is the fast first pass; a match (exact, or a
+// truncated prefix like "svchost") is then confirmed against the executable
+// path. When the path can't be read — typical for the core OS processes when
+// not elevated — only an exact name match stands; a short prefix like "task"
+// is too loose to protect on its own.
 fn is_protected_process(pid: u32, name: &str) -> bool {
     if PROTECTED_PIDS.contains(&pid) {
         return true;
     }
     let name_lower = name.to_lowercase();
-    PROTECTED_PROCESSES.iter().any(|&p| name_lower == p)
+    let exact_match = PROTECTED_PROCESSES.contains(&name_lower.as_str());
+    let prefix_match = name_lower.len() >= MIN_PROTECTED_PREFIX
+        && PROTECTED_PROCESSES
+            .iter()
+            .any(|p| p.starts_with(&name_lower));
+    if !exact_match && !prefix_match {
+        return false;
+    }
+    match platform::image_path(pid) {
+        Some(path) => {
+            platform::is_os_binary(&path)
+                && path.file_name().is_some_and(|file| {
+                    PROTECTED_PROCESSES.contains(&file.to_string_lossy().to_lowercase().as_str())
+                })
+        }
+        None => exact_match,
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+// First port of the OS's ephemeral (client) range.
+const EPHEMERAL_PORT_START: u16 = if cfg!(windows) { 49152 } else { 32768 };
+
+// UDP sockets have no Listen state, so guess from the bind. Low and
+// well-known ports are servers whatever the address; ports in the ephemeral
+// range are clients (DNS lookups, QUIC), whether bound to loopback or to the
+// wildcard address the OS hands out for outgoing datagrams.
+fn is_likely_udp_server(port: u16) -> bool {
+    port < EPHEMERAL_PORT_START || well_known_service(port).is_some()
+}
+
+// Well-known and registered ports developers actually run into. Not the full
+// IANA registry — just enough to label the common rows.
+fn well_known_service(port: u16) -> Option<&'static str> {
+    let name = match port {
+        20 | 21 => "ftp",
+        22 => "ssh",
+        23 => "telnet",
+        25 => "smtp",
+        53 => "dns",
+        67 | 68 => "dhcp",
+        80 => "http",
+        110 => "pop3",
+        123 => "ntp",
+        135 => "msrpc",
+        137..=139 => "netbios",
+        143 => "imap",
+        161 => "snmp",
+        389 => "ldap",
+        443 => "https",
+        445 => "smb",
+        465 | 587 => "smtps",
+        636 => "ldaps",
+        993 => "imaps",
+        995 => "pop3s",
+        1433 => "mssql",
+        1521 => "oracle",
+        1883 => "mqtt",
+        2375 | 2376 => "docker",
+        3000 => "node-dev",
+        3306 => "mysql",
+        3389 => "rdp",
+        4200 => "angular-dev",
+        5000 => "flask",
+        5173 => "vite",
+        5353 => "mdns",
+        5432 => "postgresql",
+        5672 => "amqp",
+        5900 => "vnc",
+        6379 => "redis",
+        8000 => "http-alt",
+        8080 => "http-proxy",
+        8443 => "https-alt",
+        9000 => "php-fpm",
+        9090 => "prometheus",
+        9200 => "elasticsearch",
+        11211 => "memcached",
+        15672 => "rabbitmq-mgmt",
+        27017 => "mongodb",
+        _ => return None,
+    };
+    Some(name)
+}
+
+// Refresh a single PID on the shared System and return its name, or an error
+// if it no longer exists.
+fn lookup_process_name(data: &AppData, pid: u32) -> Result<String, AppError> {
+    let mut system = data
+        .system
+        .lock()
+        .map_err(|_| "system mutex poisoned".to_string())?;
+    let sys_pid = Pid::from_u32(pid);
+    system.refresh_processes(ProcessesToUpdate::Some(&[sys_pid]));
+    system
+        .process(sys_pid)
+        .map(|p| p.name().to_string_lossy().to_string())
+        .ok_or_else(|| AppError::NotFound(format!("Process {} not found", pid)))
+}
+
+// Executables that forward ports on behalf of a container or WSL distro.
+fn proxy_kind(process_name: &str) -> Option<&'static str> {
+    match process_name.to_lowercase().as_str() {
+        "com.docker.backend.exe"
+        | "com.docker.proxy.exe"
+        | "vpnkit.exe"
+        | "docker-proxy"
+        | "rootlesskit" => Some("docker"),
+        "wslrelay.exe" | "wslhost.exe" | "wslservice.exe" => Some("wsl"),
+        _ => None,
+    }
 }
 
 fn get_process_info(system: &System, pid: u32) -> (String, String) {
@@ -98,268 +579,3562 @@ fn get_process_info(system: &System, pid: u32) -> (String, String) {
             .map(|p| p.to_string_lossy().to_string())
             .unwrap_or_default();
         (name, path)
+    } else if PROTECTED_PIDS.contains(&pid) {
+        // The kernel pseudo-processes (System Idle / System) often aren't in
+        // sysinfo's table; label them instead of showing "Unknown".
+        ("System".to_string(), String::new())
     } else {
         ("Unknown".to_string(), String::new())
     }
 }
 
-// Detect process elevation via the Win32 token API. Avoids the ~100 ms cost of
-// shelling `net session` on the critical startup path.
-fn is_running_as_admin() -> bool {
-    unsafe {
-        let mut token: HANDLE = HANDLE::default();
-        if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token).is_err() {
-            return false;
-        }
+#[tauri::command]
+async fn get_listening_ports(
+    options: Option<ScanOptions>,
+    app: AppHandle,
+) -> Result<AppState, AppError> {
+    // Socket enumeration plus the owners' process refresh can take a few
+    // hundred ms; run it on the blocking pool so the IPC thread stays free.
+    tauri::async_runtime::spawn_blocking(move || {
+        let data = app.state::<AppData>();
+        // UDP sockets carry no state; ask for "None" as well so bound UDP
+        // ports keep showing up alongside TCP listeners.
+        collect_ports(&data, &listening_states(), &options.unwrap_or_default())
+            .inspect_err(|e| data.log("error", format!("Port refresh failed: {}", e)))
+    })
+    .await
+    .map_err(|e| AppError::Other(e.to_string()))?
+}
 
-        let mut elevation = TOKEN_ELEVATION::default();
-        let size = std::mem::size_of::<TOKEN_ELEVATION>() as u32;
-        let mut returned = 0u32;
-        let result = GetTokenInformation(
-            token,
-            TokenElevation,
-            Some(&mut elevation as *mut _ as *mut _),
-            size,
-            &mut returned,
-        );
-        let _ = CloseHandle(token);
+// Listening rows of one process, the inverse of get_process_details'
+// listening_ports. Empty, not an error, when the PID holds no ports.
+#[tauri::command]
+fn get_ports_for_pid(pid: u32, data: State<AppData>) -> Result<Vec<PortInfo>, AppError> {
+    let options = ScanOptions {
+        pid: Some(pid),
+        include_udp_clients: true,
+        show_ignored: true,
+        ..Default::default()
+    };
+    Ok(collect_ports(&data, &listening_states(), &options)?.ports)
+}
+
+// TCP rows on `port` with their queue sizes: the listener (whose receive
+// queue is the accept backlog) and its open connections, including ones the
+// peer has closed but the server hasn't. Queues are None where the platform
+// doesn't expose them.
+#[tauri::command]
+fn get_socket_queues(port: u16, data: State<AppData>) -> Result<Vec<PortInfo>, AppError> {
+    let options = ScanOptions {
+        range: Some((port, port)),
+        protocols: Some(vec!["tcp".to_string(), "tcp6".to_string()]),
+        socket_queues: true,
+        show_ignored: true,
+        ..Default::default()
+    };
+    let states = ["Listen", "Established", "CloseWait"].map(str::to_string);
+    Ok(collect_ports(&data, &states, &options)?.ports)
+}
+
+// Listening rows grouped by PID. Groups are ordered by process name, and
+// each group's ports ascend.
+#[tauri::command]
+fn get_ports_grouped(
+    options: Option<ScanOptions>,
+    data: State<AppData>,
+) -> Result<Vec<ProcessGroup>, AppError> {
+    let state = collect_ports(&data, &listening_states(), &options.unwrap_or_default())?;
+
+    let mut groups: HashMap<u32, ProcessGroup> = HashMap::new();
+    for row in state.ports {
+        groups
+            .entry(row.pid)
+            .or_insert_with(|| ProcessGroup {
+                pid: row.pid,
+                process_name: row.process_name.clone(),
+                process_path: row.process_path.clone(),
+                is_protected: row.is_protected,
+                ports: Vec::new(),
+            })
+            .ports
+            .push(row);
+    }
+
+    let mut groups: Vec<ProcessGroup> = groups.into_values().collect();
+    for group in &mut groups {
+        group.ports.sort_by_key(|p| p.port);
+    }
+    groups.sort_by(|a, b| {
+        a.process_name
+            .to_lowercase()
+            .cmp(&b.process_name.to_lowercase())
+            .then(a.pid.cmp(&b.pid))
+    });
+    Ok(groups)
+}
+
+fn listening_states() -> Vec<String> {
+    vec!["Listen".to_string(), UDP_STATE.to_string()]
+}
+
+// UDP is connectionless, so every UDP row carries this placeholder state.
+const UDP_STATE: &str = "None";
 
-        result.is_ok() && elevation.TokenIsElevated != 0
+// Spelled out rather than taken from `Debug` so the strings the frontend and
+// `include_states` filters match on can't change under a netstat2 upgrade.
+fn tcp_state_name(state: &netstat2::TcpState) -> &'static str {
+    use netstat2::TcpState::*;
+    match state {
+        Closed => "Closed",
+        Listen => "Listen",
+        SynSent => "SynSent",
+        SynReceived => "SynReceived",
+        Established => "Established",
+        FinWait1 => "FinWait1",
+        FinWait2 => "FinWait2",
+        CloseWait => "CloseWait",
+        Closing => "Closing",
+        LastAck => "LastAck",
+        TimeWait => "TimeWait",
+        DeleteTcb => "DeleteTcb",
+        Unknown => "Unknown",
     }
 }
 
 #[tauri::command]
-fn get_listening_ports(data: State<AppData>) -> Result<AppState, String> {
-    let af_flags = AddressFamilyFlags::IPV4 | AddressFamilyFlags::IPV6;
-    let proto_flags = ProtocolFlags::TCP | ProtocolFlags::UDP;
+fn get_all_connections(
+    include_states: Vec<String>,
+    resolve_dns: Option<bool>,
+    data: State<AppData>,
+) -> Result<AppState, AppError> {
+    let options = ScanOptions {
+        resolve_dns: resolve_dns.unwrap_or(false),
+        include_udp_clients: true,
+        ..Default::default()
+    };
+    collect_ports(&data, &include_states, &options)
+}
 
-    let sockets = get_sockets_info(af_flags, proto_flags).map_err(|e| e.to_string())?;
+// Optional filters and extras for the enumeration, passed from the frontend
+// as `options`. Every field defaults to the unfiltered, cheap path, except
+// that likely-client UDP sockets are hidden unless asked for.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+#[cfg_attr(feature = "bindings", derive(TS))]
+struct ScanOptions {
+    // Reverse-resolve remote addresses. Off by default — lookups are slow.
+    resolve_dns: bool,
+    // Inclusive (min, max) local port bounds.
+    range: Option<(u16, u16)>,
+    // Only these local ports. Owners of sockets on other ports aren't
+    // refreshed either, so a handful of ports stays cheap to poll.
+    ports: Option<Vec<u16>>,
+    // "ipv4", "ipv6" or "both" (default).
+    address_family: Option<String>,
+    // Any of "tcp", "udp", "tcp6", "udp6"; None means all four. IPv6
+    // sockets are the "6" variants and their rows say so in `protocol`.
+    protocols: Option<Vec<String>>,
+    // Merge a process's IPv4 and IPv6 bind of the same port into one row
+    // whose `local_address` lists both, e.g. "0.0.0.0, ::".
+    collapse_families: bool,
+    // Merge every bind address of the same (port, PID, protocol) into one
+    // row, e.g. "127.0.0.1, 192.168.1.20". By default each address is its
+    // own row.
+    collapse_addresses: bool,
+    // "port" (default), "pid", "name" or "protocol". Unknown keys fall back
+    // to "port".
+    sort_by: Option<String>,
+    descending: Option<bool>,
+    // "only" keeps loopback-bound sockets (127.0.0.0/8, ::1), "exclude" drops
+    // them to leave just the externally reachable ones, "all" (default).
+    loopback: Option<String>,
+    // Drop protected / system-owned rows (e.g. PID 4's 139 and 445). They're
+    // included by default, flagged `is_protected`.
+    hide_protected: bool,
+    // Include processes on the user's ignore list.
+    show_ignored: bool,
+    // Only rows owned by this PID; only it gets a process refresh.
+    pid: Option<u32>,
+    // Keep UDP sockets is_likely_udp_server rates as clients. Off by default;
+    // they're mostly short-lived DNS / QUIC sockets.
+    include_udp_clients: bool,
+    // Fill PortInfo's recv_queue / send_queue for TCP rows, where the
+    // platform exposes them (Linux).
+    socket_queues: bool,
+    // Refresh every process, not just the socket owners. Set by the
+    // background watcher every FULL_REFRESH_EVERY cycles so exits and new
+    // children show up for the commands that read the shared snapshot.
+    #[serde(skip)]
+    full_refresh: bool,
+}
+
+const ALL_PROTOCOLS: [&str; 4] = ["TCP", "UDP", "TCP6", "UDP6"];
+
+// ScanOptions.protocols as the row labels to keep.
+fn parse_protocols(protocols: Option<&[String]>) -> Result<HashSet<&'static str>, AppError> {
+    let Some(protocols) = protocols else {
+        return Ok(ALL_PROTOCOLS.into_iter().collect());
+    };
+    if protocols.is_empty() {
+        return Err(AppError::InvalidInput(
+            "At least one protocol is required".to_string(),
+        ));
+    }
+    protocols
+        .iter()
+        .map(|protocol| {
+            ALL_PROTOCOLS
+                .into_iter()
+                .find(|known| known.eq_ignore_ascii_case(protocol.trim()))
+                .ok_or_else(|| {
+                    AppError::InvalidInput(format!(
+                        "Unknown protocol \"{}\" (expected tcp, udp, tcp6 or udp6)",
+                        protocol
+                    ))
+                })
+        })
+        .collect()
+}
+
+// "TCP6" -> "TCP". Rows of either family are the same protocol for
+// filtering, kills and collapsing.
+fn base_protocol(protocol: &str) -> &str {
+    protocol.trim_end_matches('6')
+}
+
+// Stable sort, so rows that tie keep their port order.
+fn sort_ports(ports: &mut [PortInfo], sort_by: Option<&str>, descending: bool) {
+    ports.sort_by_key(|p| p.port);
+    match sort_by.map(str::to_lowercase).as_deref() {
+        Some("pid") => ports.sort_by_key(|p| p.pid),
+        Some("name") => ports.sort_by_key(|p| p.process_name.to_lowercase()),
+        Some("protocol") => ports.sort_by(|a, b| a.protocol.cmp(&b.protocol)),
+        _ => {}
+    }
+    if descending {
+        ports.reverse();
+    }
+    // Favorites go first whatever the order; the sort is stable, so both
+    // groups keep it.
+    ports.sort_by_key(|p| !p.is_favorite);
+}
+
+// 0.0.0.0 and ::, or 127.0.0.1 and ::1 — the same bind, just on the other
+// address family.
+fn same_address_other_family(a: IpAddr, b: IpAddr) -> bool {
+    a.is_ipv4() != b.is_ipv4()
+        && ((a.is_unspecified() && b.is_unspecified()) || (a.is_loopback() && b.is_loopback()))
+}
+
+// Reachability of a bind address. Loopback is "local". A specific address
+// is "public" if it's globally routable, "lan" otherwise. A wildcard bind
+// (0.0.0.0 / ::) is as exposed as the machine's most public interface.
+// `has_public_ip` is only evaluated for wildcard binds.
+fn exposure(ip: IpAddr, has_public_ip: &mut impl FnMut() -> bool) -> &'static str {
+    let ip = ip.to_canonical();
+    if ip.is_loopback() {
+        "local"
+    } else if ip.is_unspecified() {
+        if has_public_ip() {
+            "public"
+        } else {
+            "lan"
+        }
+    } else if is_public_ip(ip) {
+        "public"
+    } else {
+        "lan"
+    }
+}
+
+fn exposure_rank(exposure: &str) -> u8 {
+    match exposure {
+        "public" => 2,
+        "lan" => 1,
+        _ => 0,
+    }
+}
+
+// Fold rows that differ only in local address into the first such row,
+// joining the addresses with ", ". `mergeable` decides whether a new address
+// may join a row given each address already on it.
+fn collapse_rows(ports: Vec<PortInfo>, mergeable: fn(IpAddr, IpAddr) -> bool) -> Vec<PortInfo> {
+    let mut out: Vec<PortInfo> = Vec::with_capacity(ports.len());
+    for row in ports {
+        let Ok(ip) = row.local_address.parse::<IpAddr>() else {
+            out.push(row);
+            continue;
+        };
+        let target = out.iter_mut().find(|existing| {
+            existing.port == row.port
+                && existing.pid == row.pid
+                && base_protocol(&existing.protocol) == base_protocol(&row.protocol)
+                && existing.state == row.state
+                && existing.remote_address == row.remote_address
+                && existing
+                    .local_address
+                    .split(", ")
+                    .filter_map(|a| a.parse::<IpAddr>().ok())
+                    .all(|other| mergeable(other, ip))
+        });
+        match target {
+            Some(existing) => {
+                existing.local_address =
+                    format!("{}, {}", existing.local_address, row.local_address);
+                // Spans both families now, so drop the "6".
+                if existing.protocol != row.protocol {
+                    existing.protocol = base_protocol(&row.protocol).to_string();
+                }
+                // The merged row is as exposed as its widest bind.
+                if exposure_rank(&row.exposure) > exposure_rank(&existing.exposure) {
+                    existing.exposure = row.exposure;
+                }
+            }
+            None => out.push(row),
+        }
+    }
+    out
+}
+
+const DNS_TIMEOUT: Duration = Duration::from_millis(500);
+
+// Best-effort reverse DNS for every IP not already cached. Lookups run in
+// parallel and share a single deadline, so an unreachable resolver costs at
+// most DNS_TIMEOUT per refresh rather than per address.
+fn resolve_hosts(data: &AppData, ips: HashSet<IpAddr>) -> HashMap<IpAddr, Option<String>> {
+    let mut cache = match data.dns_cache.lock() {
+        Ok(cache) => cache,
+        Err(_) => return HashMap::new(),
+    };
+
+    let pending: Vec<IpAddr> = ips
+        .iter()
+        .filter(|ip| !cache.contains_key(ip))
+        .copied()
+        .collect();
+    if !pending.is_empty() {
+        let (tx, rx) = mpsc::channel();
+        for ip in &pending {
+            let tx = tx.clone();
+            let ip = *ip;
+            std::thread::spawn(move || {
+                let _ = tx.send((ip, dns_lookup::lookup_addr(&ip).ok()));
+            });
+        }
+        drop(tx);
+
+        let deadline = Instant::now() + DNS_TIMEOUT;
+        while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+            match rx.recv_timeout(remaining) {
+                Ok((ip, host)) => {
+                    cache.insert(ip, host);
+                }
+                Err(_) => break,
+            }
+        }
+        for ip in pending {
+            cache.entry(ip).or_insert(None);
+        }
+    }
+
+    ips.into_iter()
+        .map(|ip| {
+            let host = cache.get(&ip).cloned().flatten();
+            (ip, host)
+        })
+        .collect()
+}
+
+// Shared enumeration behind every port-listing command and the background
+// watcher. Only rows whose state is in `include_states` are returned.
+fn collect_ports(
+    data: &AppData,
+    include_states: &[String],
+    options: &ScanOptions,
+) -> Result<AppState, AppError> {
+    if let Some((min, max)) = options.range {
+        if min > max {
+            return Err(AppError::InvalidInput(format!(
+                "Invalid port range: {} is greater than {}",
+                min, max
+            )));
+        }
+    }
+
+    let protocols = parse_protocols(options.protocols.as_deref())?;
+    let mut af_flags = match options
+        .address_family
+        .as_deref()
+        .map(str::to_lowercase)
+        .as_deref()
+    {
+        Some("ipv4") => AddressFamilyFlags::IPV4,
+        Some("ipv6") => AddressFamilyFlags::IPV6,
+        Some("both") | None => AddressFamilyFlags::IPV4 | AddressFamilyFlags::IPV6,
+        Some(other) => {
+            return Err(AppError::InvalidInput(format!(
+                "Unknown address family \"{}\" (expected ipv4, ipv6 or both)",
+                other
+            )))
+        }
+    };
+    let keep_loopback: Option<bool> = match options
+        .loopback
+        .as_deref()
+        .map(str::to_lowercase)
+        .as_deref()
+    {
+        Some("only") => Some(true),
+        Some("exclude") => Some(false),
+        Some("all") | None => None,
+        Some(other) => {
+            return Err(AppError::InvalidInput(format!(
+                "Unknown loopback filter \"{}\" (expected only, exclude or all)",
+                other
+            )))
+        }
+    };
+    // Only ask the OS for the tables some requested protocol needs.
+    if !protocols.iter().any(|p| !p.ends_with('6')) {
+        af_flags.remove(AddressFamilyFlags::IPV4);
+    }
+    if !protocols.iter().any(|p| p.ends_with('6')) {
+        af_flags.remove(AddressFamilyFlags::IPV6);
+    }
+    let proto_flags = match (
+        protocols.iter().any(|p| p.starts_with("TCP")),
+        protocols.iter().any(|p| p.starts_with("UDP")),
+    ) {
+        (true, false) => ProtocolFlags::TCP,
+        (false, true) => ProtocolFlags::UDP,
+        _ => ProtocolFlags::TCP | ProtocolFlags::UDP,
+    };
+
+    // e.g. address_family "ipv4" with only "tcp6" requested.
+    let sockets = if af_flags.is_empty() {
+        Vec::new()
+    } else {
+        get_sockets_info(af_flags, proto_flags).map_err(|e| e.to_string())?
+    };
+
+    let wanted: HashSet<String> = include_states.iter().map(|s| s.to_lowercase()).collect();
 
     // Refresh process info on the shared System instance. Cheaper than
-    // building a new one per poll.
+    // building a new one per poll. Only the socket owners are needed, and a
+    // machine typically has hundreds of processes but a few dozen owners;
+    // see benches/process_refresh.rs.
     let mut system = data
         .system
         .lock()
         .map_err(|_| "system mutex poisoned".to_string())?;
-    system.refresh_processes(ProcessesToUpdate::All);
+    if options.full_refresh {
+        system.refresh_processes(ProcessesToUpdate::All);
+    } else {
+        let owners: HashSet<u32> = sockets
+            .iter()
+            .filter(|s| {
+                options
+                    .ports
+                    .as_ref()
+                    .is_none_or(|ports| ports.contains(&s.local_port()))
+            })
+            .flat_map(|s| s.associated_pids.iter().copied())
+            .filter(|&pid| options.pid.is_none_or(|wanted| wanted == pid))
+            .collect();
+        let pids: Vec<Pid> = owners.into_iter().map(Pid::from_u32).collect();
+        system.refresh_processes(ProcessesToUpdate::Some(&pids));
+    }
+
+    // Interface enumeration only happens if a wildcard bind needs it, and
+    // at most once per scan.
+    let mut public_ip: Option<bool> = None;
+    let tcp_queues = options.socket_queues.then(platform::tcp_queues);
+    let mut has_public_ip = || {
+        *public_ip.get_or_insert_with(|| platform::local_addresses().into_iter().any(is_public_ip))
+    };
 
     let mut ports: Vec<PortInfo> = Vec::new();
-    let mut seen: HashSet<(u16, u32)> = HashSet::new();
+    // One row per distinct bind: the same PID listening on 127.0.0.1 and
+    // 0.0.0.0 yields two rows unless the caller asks to collapse them.
+    #[allow(clippy::type_complexity)]
+    let mut seen: HashSet<(u16, u32, String, IpAddr, String, Option<SocketAddr>)> = HashSet::new();
 
     for socket in sockets {
-        let (protocol, local_port, local_addr) = match &socket.protocol_socket_info {
-            ProtocolSocketInfo::Tcp(tcp) => {
-                if tcp.state != netstat2::TcpState::Listen {
-                    continue;
+        let (protocol, local_port, local_ip, state, remote, likely_server) =
+            match &socket.protocol_socket_info {
+                ProtocolSocketInfo::Tcp(tcp) => {
+                    let is_listen = tcp.state == netstat2::TcpState::Listen;
+                    let remote = if is_listen {
+                        None
+                    } else {
+                        Some(SocketAddr::new(tcp.remote_addr, tcp.remote_port))
+                    };
+                    (
+                        "TCP".to_string(),
+                        tcp.local_port,
+                        tcp.local_addr,
+                        tcp_state_name(&tcp.state).to_string(),
+                        remote,
+                        is_listen,
+                    )
                 }
-                (
-                    "TCP".to_string(),
-                    tcp.local_port,
-                    tcp.local_addr.to_string(),
-                )
-            }
-            ProtocolSocketInfo::Udp(udp) => (
-                "UDP".to_string(),
-                udp.local_port,
-                udp.local_addr.to_string(),
-            ),
+                ProtocolSocketInfo::Udp(udp) => (
+                    "UDP".to_string(),
+                    udp.local_port,
+                    udp.local_addr,
+                    UDP_STATE.to_string(),
+                    None,
+                    is_likely_udp_server(udp.local_port),
+                ),
+            };
+        let local_addr = local_ip.to_string();
+        let is_udp = protocol == "UDP";
+        let protocol = if local_ip.is_ipv6() {
+            format!("{}6", protocol)
+        } else {
+            protocol
         };
 
+        if !protocols.contains(protocol.as_str()) {
+            continue;
+        }
+        if !wanted.contains(&state.to_lowercase()) {
+            continue;
+        }
+        if is_udp && !likely_server && !options.include_udp_clients {
+            continue;
+        }
+        if let Some((min, max)) = options.range {
+            if !(min..=max).contains(&local_port) {
+                continue;
+            }
+        }
+        if options
+            .ports
+            .as_ref()
+            .is_some_and(|ports| !ports.contains(&local_port))
+        {
+            continue;
+        }
+        // to_canonical so an IPv4-mapped ::ffff:127.0.0.1 counts as loopback.
+        if let Some(keep) = keep_loopback {
+            if local_ip.to_canonical().is_loopback() != keep {
+                continue;
+            }
+        }
+
         for pid in &socket.associated_pids {
             let pid_u32 = *pid;
-            if seen.contains(&(local_port, pid_u32)) {
+            if options.pid.is_some_and(|wanted| wanted != pid_u32) {
+                continue;
+            }
+            if !seen.insert((
+                local_port,
+                pid_u32,
+                protocol.clone(),
+                local_ip,
+                state.clone(),
+                remote,
+            )) {
+                continue;
+            }
+
+            let (process_name, process_path) = get_process_info(&system, pid_u32);
+            if !options.show_ignored && data.is_ignored(&process_name) {
                 continue;
             }
-            seen.insert((local_port, pid_u32));
+            let process_start_time = system
+                .process(Pid::from_u32(pid_u32))
+                .map_or(0, |p| p.start_time());
+            let is_protected = data.is_protected(pid_u32, &process_name);
+            if is_protected && options.hide_protected {
+                continue;
+            }
+            let proxy = proxy_kind(&process_name);
+            let queues = tcp_queues.as_ref().filter(|_| !is_udp).and_then(|queues| {
+                let unspecified = if local_ip.is_ipv6() {
+                    IpAddr::V6(Ipv6Addr::UNSPECIFIED)
+                } else {
+                    IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+                };
+                let remote = remote.unwrap_or(SocketAddr::new(unspecified, 0));
+                queues.get(&(SocketAddr::new(local_ip, local_port), remote))
+            });
+
+            ports.push(PortInfo {
+                pid: pid_u32,
+                port: local_port,
+                protocol: protocol.clone(),
+                process_name,
+                process_path,
+                is_protected,
+                local_address: local_addr.clone(),
+                state: state.clone(),
+                service_hint: well_known_service(local_port).map(str::to_string),
+                remote_address: remote.map(|r| r.to_string()),
+                remote_host: None,
+                remote_country: None,
+                is_proxied: proxy.is_some(),
+                proxy_kind: proxy.map(str::to_string),
+                likely_server,
+                exposure: exposure(local_ip, &mut has_public_ip).to_string(),
+                process_start_time,
+                recv_queue: queues.map(|q| q.0),
+                send_queue: queues.map(|q| q.1),
+                is_favorite: data.is_favorite(&process_name),
+            });
+        }
+    }
+    // Rows are built; release the shared System before sorting so other
+    // commands (details panel, kills) aren't held up by this poll.
+    drop(system);
+
+    if options.collapse_addresses {
+        ports = collapse_rows(ports, |_, _| true);
+    } else if options.collapse_families {
+        ports = collapse_rows(ports, same_address_other_family);
+    }
+
+    if options.resolve_dns {
+        let ips: HashSet<IpAddr> = ports
+            .iter()
+            .filter_map(|p| p.remote_address.as_deref()?.parse::<SocketAddr>().ok())
+            .map(|addr| addr.ip())
+            .collect();
+        let hosts = resolve_hosts(data, ips);
+        for p in &mut ports {
+            if let Some(addr) = p
+                .remote_address
+                .as_deref()
+                .and_then(|r| r.parse::<SocketAddr>().ok())
+            {
+                p.remote_host = hosts.get(&addr.ip()).cloned().flatten();
+            }
+        }
+    }
+
+    // Lookups are local and cheap, so they run whenever a database is loaded.
+    if let Ok(geoip) = data.geoip.lock() {
+        if let Some(reader) = geoip.as_ref() {
+            for p in &mut ports {
+                p.remote_country = p
+                    .remote_address
+                    .as_deref()
+                    .and_then(|r| r.parse::<SocketAddr>().ok())
+                    .and_then(|addr| lookup_country(reader, addr.ip()));
+            }
+        }
+    }
+
+    sort_ports(
+        &mut ports,
+        options.sort_by.as_deref(),
+        options.descending.unwrap_or(false),
+    );
+
+    Ok(AppState::new(ports, unix_now(), data.is_admin))
+}
+
+#[tauri::command]
+fn get_connection_stats(data: State<AppData>) -> Result<ConnectionStats, AppError> {
+    let af_flags = AddressFamilyFlags::IPV4 | AddressFamilyFlags::IPV6;
+    let proto_flags = ProtocolFlags::TCP | ProtocolFlags::UDP;
+    let sockets = get_sockets_info(af_flags, proto_flags).map_err(|e| e.to_string())?;
+
+    let mut listening_count = 0;
+    let mut established_count = 0;
+    let mut per_pid: HashMap<u32, u32> = HashMap::new();
+    for socket in &sockets {
+        if let ProtocolSocketInfo::Tcp(tcp) = &socket.protocol_socket_info {
+            match tcp.state {
+                netstat2::TcpState::Listen => listening_count += 1,
+                netstat2::TcpState::Established => established_count += 1,
+                _ => {}
+            }
+        }
+        for pid in &socket.associated_pids {
+            *per_pid.entry(*pid).or_default() += 1;
+        }
+    }
+
+    // Group by name rather than PID so multi-process apps (browsers, Docker)
+    // show up as one line.
+    let mut per_name: HashMap<String, u32> = HashMap::new();
+    {
+        let mut system = data
+            .system
+            .lock()
+            .map_err(|_| "system mutex poisoned".to_string())?;
+        let pids: Vec<Pid> = per_pid.keys().map(|&p| Pid::from_u32(p)).collect();
+        system.refresh_processes(ProcessesToUpdate::Some(&pids));
+        for (pid, count) in per_pid {
+            let (name, _) = get_process_info(&system, pid);
+            *per_name.entry(name).or_default() += count;
+        }
+    }
+
+    let mut by_process: Vec<(String, u32)> = per_name.into_iter().collect();
+    by_process.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    Ok(ConnectionStats {
+        total_connections: sockets.len(),
+        listening_count,
+        established_count,
+        by_process,
+    })
+}
+
+// Machine-wide load for the header bar. Memory is in bytes, CPU in percent.
+#[tauri::command]
+fn get_system_stats(data: State<AppData>) -> Result<SystemStats, AppError> {
+    let mut system = data
+        .system
+        .lock()
+        .map_err(|_| "system mutex poisoned".to_string())?;
+
+    // Same two-sample rule as per-process CPU: the shared System is created
+    // without CPU info, so the first call takes a baseline and waits out the
+    // minimum interval (without holding the lock) before the real reading.
+    if system.cpus().is_empty() {
+        system.refresh_cpu_usage();
+        drop(system);
+        std::thread::sleep(MINIMUM_CPU_UPDATE_INTERVAL);
+        system = data
+            .system
+            .lock()
+            .map_err(|_| "system mutex poisoned".to_string())?;
+    }
+    system.refresh_cpu_usage();
+    system.refresh_memory();
+
+    Ok(SystemStats {
+        total_memory: system.total_memory(),
+        used_memory: system.used_memory(),
+        cpu_usage_total: system.global_cpu_usage(),
+        per_core: system.cpus().iter().map(|cpu| cpu.cpu_usage()).collect(),
+    })
+}
+
+// Glob syntax for the protected and ignore lists and for search: `*`
+// matches any run of characters (including none), `?` exactly one, and
+// everything else is literal. Matching is case-insensitive and covers the
+// whole string, so `python*` matches python3.exe but not mypython.exe.
+fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?'])
+}
+
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text index it's currently matched up
+    // to, for backtracking.
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            // Let the last `*` swallow one more character and retry.
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+// List entries are stored lowercased. One without wildcards is an exact
+// name, as before globs were supported.
+fn name_matches(pattern: &str, name: &str) -> bool {
+    if is_glob(pattern) {
+        glob_match(pattern, name)
+    } else {
+        pattern == name.to_lowercase()
+    }
+}
+
+// Plain queries are case-insensitive substring matches on any field; a glob
+// must match a whole process name, path or port number. `query` must
+// already be lowercased.
+fn matches_query(port: &PortInfo, query: &str) -> bool {
+    if is_glob(query) {
+        return glob_match(query, &port.process_name)
+            || glob_match(query, &port.process_path)
+            || glob_match(query, &port.port.to_string());
+    }
+    port.process_name.to_lowercase().contains(query)
+        || port.process_path.to_lowercase().contains(query)
+        || port.port.to_string().contains(query)
+        || port.protocol.to_lowercase().contains(query)
+}
+
+#[tauri::command]
+fn search_ports(query: String, data: State<AppData>) -> Result<AppState, AppError> {
+    let mut state = collect_ports(&data, &listening_states(), &ScanOptions::default())?;
+    let query = query.trim().to_lowercase();
+    if !query.is_empty() {
+        state.retain_ports(|p| matches_query(p, &query));
+    }
+    Ok(state)
+}
+
+// A row's "TCP6" / "UDP6" is accepted too, so `protocol` can be passed back
+// as-is; kills and lookups cover both families.
+fn normalize_protocol(protocol: &str) -> Result<&'static str, AppError> {
+    match protocol.to_lowercase().as_str() {
+        "tcp" | "tcp6" => Ok("tcp"),
+        "udp" | "udp6" => Ok("udp"),
+        other => Err(AppError::InvalidInput(format!(
+            "Unknown protocol: {}",
+            other
+        ))),
+    }
+}
+
+// Cheap post-kill confirmation for a single row: None means the port is
+// free. Only socket owners are refreshed, not every process.
+#[tauri::command]
+fn check_port(
+    port: u16,
+    protocol: Option<String>,
+    data: State<AppData>,
+) -> Result<Option<PortInfo>, AppError> {
+    let protocol = protocol.as_deref().map(normalize_protocol).transpose()?;
+    find_listener(&data, port, protocol)
+}
+
+fn find_listener(
+    data: &AppData,
+    port: u16,
+    protocol: Option<&str>,
+) -> Result<Option<PortInfo>, AppError> {
+    // Any holder counts here, including a UDP client socket or an ignored
+    // process.
+    let options = ScanOptions {
+        range: Some((port, port)),
+        include_udp_clients: true,
+        show_ignored: true,
+        ..Default::default()
+    };
+    let state = collect_ports(data, &listening_states(), &options)?;
+    Ok(state.ports.into_iter().find(|p| {
+        protocol.is_none_or(|proto| base_protocol(&p.protocol).eq_ignore_ascii_case(proto))
+    }))
+}
+
+// Force-close a lingering TCP connection (e.g. one a dead server left in
+// CLOSE_WAIT) so its local port can be bound again. The local address is
+// looked up from the live socket table.
+#[tauri::command]
+fn reset_connection(
+    local_port: u16,
+    remote_addr: String,
+    remote_port: u16,
+) -> Result<(), AppError> {
+    let remote_ip: IpAddr = remote_addr
+        .parse()
+        .map_err(|_| AppError::InvalidInput(format!("Invalid remote address: {}", remote_addr)))?;
+    let IpAddr::V4(remote_ip) = remote_ip.to_canonical() else {
+        return Err(AppError::InvalidInput(
+            "Only IPv4 connections can be reset".to_string(),
+        ));
+    };
+
+    let sockets = get_sockets_info(AddressFamilyFlags::IPV4, ProtocolFlags::TCP)
+        .map_err(|e| e.to_string())?;
+    let local_ip = sockets
+        .iter()
+        .find_map(|socket| match &socket.protocol_socket_info {
+            ProtocolSocketInfo::Tcp(tcp)
+                if tcp.local_port == local_port
+                    && tcp.remote_port == remote_port
+                    && tcp.remote_addr == IpAddr::V4(remote_ip) =>
+            {
+                Some(tcp.local_addr)
+            }
+            _ => None,
+        })
+        .ok_or_else(|| {
+            AppError::NotFound(format!(
+                "No TCP connection from port {} to {}:{}",
+                local_port, remote_ip, remote_port
+            ))
+        })?;
+    let IpAddr::V4(local_ip) = local_ip else {
+        return Err(AppError::InvalidInput(
+            "Only IPv4 connections can be reset".to_string(),
+        ));
+    };
+
+    platform::reset_tcp_connection(
+        SocketAddrV4::new(local_ip, local_port),
+        SocketAddrV4::new(remote_ip, remote_port),
+    )
+}
+
+const PROBE_TIMEOUT: Duration = Duration::from_millis(500);
+
+#[derive(Serialize, Clone)]
+#[cfg_attr(feature = "bindings", derive(TS))]
+pub struct ProbeResult {
+    pub port: u16,
+    pub protocol: String,
+    // TCP: "open", "refused" or "timeout". UDP has no handshake to test, so
+    // only "listening" or "not_listening". "error" for anything else.
+    pub status: String,
+    pub message: String,
+}
+
+// Actively connect to a local port. A listener that the netstat view shows
+// but that refuses or times out here is bound but blocked.
+#[tauri::command]
+fn probe_port(port: u16, protocol: Option<String>) -> Result<ProbeResult, AppError> {
+    let protocol = normalize_protocol(protocol.as_deref().unwrap_or("tcp"))?;
+    let (status, message) = if protocol == "udp" {
+        if pids_on_port(port, Some("udp"))?.is_empty() {
+            ("not_listening", format!("Nothing is bound to UDP {}", port))
+        } else {
+            ("listening", format!("UDP {} has a listener", port))
+        }
+    } else {
+        let addr = SocketAddr::from(([127, 0, 0, 1], port));
+        match std::net::TcpStream::connect_timeout(&addr, PROBE_TIMEOUT) {
+            Ok(_) => ("open", format!("Connected to {}", addr)),
+            Err(e) if e.kind() == std::io::ErrorKind::ConnectionRefused => {
+                ("refused", format!("{} refused the connection", addr))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::TimedOut => (
+                "timeout",
+                format!("No answer from {} within {:?}", addr, PROBE_TIMEOUT),
+            ),
+            Err(e) => ("error", e.to_string()),
+        }
+    };
+    Ok(ProbeResult {
+        port,
+        protocol: protocol.to_uppercase(),
+        status: status.to_string(),
+        message,
+    })
+}
+
+// Port ranges the OS has excluded from binding (Hyper-V, WSL and Docker
+// reserve these on Windows). A port inside one reports "in use" even though
+// no process is listening on it.
+#[tauri::command]
+fn get_reserved_port_ranges(protocol: String) -> Result<Vec<(u16, u16)>, AppError> {
+    platform::reserved_port_ranges(normalize_protocol(&protocol)?).map_err(AppError::from)
+}
+
+// Whether `text` contains `port` as a standalone number, so 80 matches
+// "--port 80" or ":80" but not 8080 or 1800.
+fn mentions_port(text: &str, port: u16) -> bool {
+    let needle = port.to_string();
+    text.match_indices(&needle).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + needle.len()..].chars().next();
+        !before.is_some_and(|c| c.is_ascii_digit()) && !after.is_some_and(|c| c.is_ascii_digit())
+    })
+}
+
+// Services whose configured command line mentions `port` — the likely
+// culprits when something grabs a port again right after it's freed.
+// Heuristic: it only sees ports passed as literal arguments.
+#[tauri::command]
+fn find_port_in_service_configs(port: u16) -> Result<Vec<String>, AppError> {
+    let mut names: Vec<String> = platform::service_command_lines()?
+        .into_iter()
+        .filter(|(_, command)| mentions_port(command, port))
+        .map(|(name, _)| name)
+        .collect();
+    names.sort_by_key(|name| name.to_lowercase());
+    names.dedup();
+    Ok(names)
+}
+
+#[tauri::command]
+fn is_port_reserved(port: u16, protocol: String) -> bool {
+    get_reserved_port_ranges(protocol)
+        .map(|ranges| {
+            ranges
+                .iter()
+                .any(|&(start, end)| (start..=end).contains(&port))
+        })
+        .unwrap_or(false)
+}
+
+// One socket on the diagnosed port, in any state.
+#[derive(Serialize, Clone)]
+#[cfg_attr(feature = "bindings", derive(TS))]
+pub struct PortSocket {
+    // None for sockets no process owns any more (Linux TIME_WAIT).
+    pub pid: Option<u32>,
+    pub process_name: Option<String>,
+    pub protocol: String,
+    pub state: String,
+    pub local_address: String,
+    pub remote_address: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+#[cfg_attr(feature = "bindings", derive(TS))]
+pub struct PortDiagnosis {
+    pub port: u16,
+    pub sockets: Vec<PortSocket>,
+    // "TCP" / "UDP" when the port sits in an OS-reserved range for it.
+    pub reserved_for: Vec<String>,
+    // e.g. "Port 5000 held by 2 sockets: PID 1234 LISTEN (TCP), PID 5678
+    // TIME_WAIT (TCP)".
+    pub summary: String,
+}
+
+// "TimeWait" -> "TIME_WAIT", the spelling netstat and ss users expect.
+fn state_label(state: &str) -> String {
+    let mut label = String::new();
+    for (i, c) in state.chars().enumerate() {
+        if i > 0 && c.is_ascii_uppercase() {
+            label.push('_');
+        }
+        label.push(c.to_ascii_uppercase());
+    }
+    label
+}
+
+// Everything that could make a bind on `port` fail, in one report: every
+// socket on it regardless of state or protocol (lingering TIME_WAIT and
+// connected sockets included), plus reserved-range conflicts.
+#[tauri::command]
+fn diagnose_port(port: u16, data: State<AppData>) -> Result<PortDiagnosis, AppError> {
+    let sockets = get_sockets_info(
+        AddressFamilyFlags::IPV4 | AddressFamilyFlags::IPV6,
+        ProtocolFlags::TCP | ProtocolFlags::UDP,
+    )
+    .map_err(|e| e.to_string())?;
+
+    let mut system = data
+        .system
+        .lock()
+        .map_err(|_| "system mutex poisoned".to_string())?;
+    let owners: Vec<Pid> = sockets
+        .iter()
+        .filter(|s| s.local_port() == port)
+        .flat_map(|s| s.associated_pids.iter().copied())
+        .map(Pid::from_u32)
+        .collect();
+    system.refresh_processes(ProcessesToUpdate::Some(&owners));
+
+    let mut found = Vec::new();
+    for socket in &sockets {
+        let (protocol, local, state, remote) = match &socket.protocol_socket_info {
+            ProtocolSocketInfo::Tcp(tcp) => (
+                "TCP",
+                SocketAddr::new(tcp.local_addr, tcp.local_port),
+                tcp_state_name(&tcp.state),
+                (tcp.state != netstat2::TcpState::Listen)
+                    .then(|| SocketAddr::new(tcp.remote_addr, tcp.remote_port).to_string()),
+            ),
+            ProtocolSocketInfo::Udp(udp) => (
+                "UDP",
+                SocketAddr::new(udp.local_addr, udp.local_port),
+                UDP_STATE,
+                None,
+            ),
+        };
+        if local.port() != port {
+            continue;
+        }
+        let pids: Vec<Option<u32>> = if socket.associated_pids.is_empty() {
+            vec![None]
+        } else {
+            socket.associated_pids.iter().copied().map(Some).collect()
+        };
+        for pid in pids {
+            found.push(PortSocket {
+                pid,
+                process_name: pid.map(|pid| get_process_info(&system, pid).0),
+                protocol: protocol.to_string(),
+                state: state.to_string(),
+                local_address: local.ip().to_string(),
+                remote_address: remote.clone(),
+            });
+        }
+    }
+    drop(system);
+
+    let reserved_for: Vec<String> = ["tcp", "udp"]
+        .into_iter()
+        .filter(|protocol| is_port_reserved(port, protocol.to_string()))
+        .map(str::to_uppercase)
+        .collect();
+
+    let mut summary = if found.is_empty() {
+        format!("Port {} is not held by any socket", port)
+    } else {
+        let entries: Vec<String> = found
+            .iter()
+            .map(|s| {
+                let owner = s
+                    .pid
+                    .map_or_else(|| "no owner".to_string(), |pid| format!("PID {}", pid));
+                if s.state == UDP_STATE {
+                    format!("{} ({})", owner, s.protocol)
+                } else {
+                    format!("{} {} ({})", owner, state_label(&s.state), s.protocol)
+                }
+            })
+            .collect();
+        format!(
+            "Port {} held by {} socket{}: {}",
+            port,
+            found.len(),
+            if found.len() == 1 { "" } else { "s" },
+            entries.join(", ")
+        )
+    };
+    if !reserved_for.is_empty() {
+        summary.push_str(&format!(
+            "; inside an OS-reserved range for {}",
+            reserved_for.join(" and ")
+        ));
+    }
+
+    Ok(PortDiagnosis {
+        port,
+        sockets: found,
+        reserved_for,
+        summary,
+    })
+}
+
+// Every process holding a socket on `port`, read from the OS handle / fd
+// tables rather than the netstat owner. A child that inherited the
+// listening socket keeps the port bound after its parent is killed, while
+// netstat still names the dead parent; it shows up here. Walking every
+// handle on the machine takes a while, hence the blocking pool.
+#[tauri::command]
+async fn find_socket_handle_holders(port: u16) -> Result<Vec<u32>, AppError> {
+    tauri::async_runtime::spawn_blocking(move || platform::socket_handle_holders(port))
+        .await
+        .map_err(|e| AppError::Other(e.to_string()))?
+}
+
+const DEFAULT_WATCH_INTERVAL_MS: u64 = 2000;
+// Floor for caller-supplied intervals; faster polling just burns a core.
+const MIN_WATCH_INTERVAL_MS: u64 = 500;
+// Every Nth watcher cycle refreshes every process (picking up exits and
+// renames); the others only refresh PIDs that currently own sockets.
+const FULL_REFRESH_EVERY: u64 = 10;
+
+fn clamp_watch_interval(interval_ms: u64) -> u64 {
+    interval_ms.max(MIN_WATCH_INTERVAL_MS)
+}
+
+// Cycle 0 is full so the first snapshot sees every process.
+fn is_full_refresh_cycle(cycle: u64) -> bool {
+    cycle % FULL_REFRESH_EVERY == 0
+}
+
+// Returns the interval actually used after clamping to MIN_WATCH_INTERVAL_MS.
+#[tauri::command]
+fn start_port_watch(app: AppHandle, interval_ms: u64) -> Result<u64, AppError> {
+    if interval_ms == 0 {
+        return Err(AppError::InvalidInput(
+            "Interval must be greater than zero".to_string(),
+        ));
+    }
+    let interval_ms = clamp_watch_interval(interval_ms);
+    spawn_port_watch(app, interval_ms);
+    Ok(interval_ms)
+}
+
+// Start (or restart) the background watcher thread.
+fn spawn_port_watch(app: AppHandle, interval_ms: u64) {
+    let data = app.state::<AppData>();
+    let generation = data.watch_generation.fetch_add(1, Ordering::SeqCst) + 1;
+    data.watch_active.store(true, Ordering::SeqCst);
+
+    std::thread::spawn(move || {
+        let mut last: Option<Vec<PortInfo>> = None;
+        let mut last_watchlist: Option<Vec<PortInfo>> = None;
+        // Only log a refresh error when it changes, not on every cycle.
+        let mut last_error: Option<String> = None;
+        let mut cycle: u64 = 0;
+        loop {
+            let data = app.state::<AppData>();
+            if data.watch_generation.load(Ordering::SeqCst) != generation {
+                break;
+            }
+            let watchlist = data.watchlist.lock().map(|w| w.clone()).unwrap_or_default();
+            // Watches and guards apply to ignored processes too; they're
+            // dropped only from what's shown.
+            let options = if watchlist.is_empty() {
+                ScanOptions {
+                    full_refresh: is_full_refresh_cycle(cycle),
+                    show_ignored: true,
+                    ..Default::default()
+                }
+            } else {
+                ScanOptions {
+                    ports: Some(watchlist_scan_ports(&data, &watchlist)),
+                    show_ignored: true,
+                    ..Default::default()
+                }
+            };
+            cycle += 1;
+            match collect_ports(&data, &listening_states(), &options) {
+                Ok(mut state) if !watchlist.is_empty() => {
+                    last_error = None;
+                    notify_watched_ports(&app, &data, &state.ports);
+                    enforce_port_guards(&app, &data, &state.ports);
+                    update_tray_alert(&app, &data, &state.ports);
+                    // An ignored process still blocks the port, so it stays.
+                    state.retain_ports(|p| watchlist.contains(&p.port));
+                    if last_watchlist.as_ref() != Some(&state.ports) {
+                        last_watchlist = Some(state.ports.clone());
+                        let _ = app.emit(WATCHLIST_UPDATED_EVENT, &state);
+                    }
+                }
+                Ok(mut state) => {
+                    last_error = None;
+                    // So re-setting the same watchlist pushes a fresh snapshot.
+                    last_watchlist = None;
+                    notify_watched_ports(&app, &data, &state.ports);
+                    enforce_port_guards(&app, &data, &state.ports);
+                    update_tray_alert(&app, &data, &state.ports);
+                    state.retain_ports(|p| !data.is_ignored(&p.process_name));
+                    update_tray_menu(&app, &state.ports);
+                    // Only push when the listener set actually changed; the UI
+                    // keeps its last snapshot otherwise.
+                    if last.as_ref() != Some(&state.ports) {
+                        if let Some(previous) = &last {
+                            let _ = app.emit(PORTS_DIFF_EVENT, diff_ports(previous, &state.ports));
+                        }
+                        last = Some(state.ports.clone());
+                        let _ = app.emit("ports-updated", &state);
+                    }
+                }
+                Err(e) => {
+                    let message = e.to_string();
+                    if last_error.as_ref() != Some(&message) {
+                        data.log("warn", format!("Background refresh failed: {}", message));
+                        last_error = Some(message);
+                    }
+                }
+            }
+            std::thread::sleep(Duration::from_millis(interval_ms));
+        }
+    });
+}
+
+const WATCHLIST_UPDATED_EVENT: &str = "watchlist-updated";
+
+// What a watchlist cycle scans: the watchlist itself plus any watched or
+// guarded ports, which would otherwise go unchecked while it's set.
+fn watchlist_scan_ports(data: &AppData, watchlist: &[u16]) -> Vec<u16> {
+    let mut ports: HashSet<u16> = watchlist.iter().copied().collect();
+    if let Ok(watched) = data.watched_ports.lock() {
+        ports.extend(watched.keys());
+    }
+    if let Ok(guarded) = data.guarded_ports.lock() {
+        ports.extend(guarded.keys());
+    }
+    ports.into_iter().collect()
+}
+
+// Narrow the background refresh to `ports`: only their sockets are resolved
+// and only their owners refreshed, and each change is pushed as
+// "watchlist-updated" instead of "ports-updated". The tray's dev-port list
+// isn't refreshed meanwhile. An empty list goes back to the full scan.
+#[tauri::command]
+fn set_watchlist(ports: Vec<u16>, app: AppHandle, data: State<AppData>) -> Result<(), AppError> {
+    let active = !ports.is_empty();
+    *data
+        .watchlist
+        .lock()
+        .map_err(|_| "watchlist mutex poisoned".to_string())? = ports;
+    if active && !data.watch_active.load(Ordering::SeqCst) {
+        spawn_port_watch(app, DEFAULT_WATCH_INTERVAL_MS);
+    }
+    Ok(())
+}
+
+const PORTS_DIFF_EVENT: &str = "ports-diff";
+
+// What changed between two watcher snapshots. Rows are matched on (port,
+// PID, protocol, local address); a matched row whose other fields differ
+// (state, exposure, ...) is `changed` and carries the new values.
+#[derive(Serialize, Clone)]
+#[cfg_attr(feature = "bindings", derive(TS))]
+pub struct PortDiff {
+    pub added: Vec<PortInfo>,
+    pub removed: Vec<PortInfo>,
+    pub changed: Vec<PortInfo>,
+}
+
+fn diff_ports(previous: &[PortInfo], current: &[PortInfo]) -> PortDiff {
+    let key = |p: &PortInfo| (p.port, p.pid, p.protocol.clone(), p.local_address.clone());
+    let before: HashMap<_, &PortInfo> = previous.iter().map(|p| (key(p), p)).collect();
+    let after: HashSet<_> = current.iter().map(key).collect();
+
+    let mut diff = PortDiff {
+        added: Vec::new(),
+        removed: Vec::new(),
+        changed: Vec::new(),
+    };
+    for row in current {
+        match before.get(&key(row)) {
+            None => diff.added.push(row.clone()),
+            Some(old) if *old != row => diff.changed.push(row.clone()),
+            Some(_) => {}
+        }
+    }
+    diff.removed = previous
+        .iter()
+        .filter(|p| !after.contains(&key(*p)))
+        .cloned()
+        .collect();
+    diff
+}
+
+#[tauri::command]
+fn stop_port_watch(data: State<AppData>) {
+    data.watch_generation.fetch_add(1, Ordering::SeqCst);
+    data.watch_active.store(false, Ordering::SeqCst);
+}
+
+// Fire an OS notification for each PID that newly appears on a watched port.
+// A port's alert state resets once it's free again.
+fn notify_watched_ports(app: &AppHandle, data: &AppData, ports: &[PortInfo]) {
+    let Ok(mut watched) = data.watched_ports.lock() else {
+        return;
+    };
+    for (port, alerted) in watched.iter_mut() {
+        let holders: Vec<&PortInfo> = ports.iter().filter(|p| p.port == *port).collect();
+        if holders.is_empty() {
+            alerted.clear();
+            continue;
+        }
+        for holder in holders {
+            if alerted.insert(holder.pid) {
+                let _ = app
+                    .notification()
+                    .builder()
+                    .title("PortKiller")
+                    .body(format!(
+                        "Port {} now used by {} (PID {})",
+                        port, holder.process_name, holder.pid
+                    ))
+                    .show();
+            }
+        }
+    }
+}
+
+// Watching needs the background refresh, so start it if the UI hasn't.
+#[tauri::command]
+fn watch_port(port: u16, app: AppHandle, data: State<AppData>) -> Result<(), AppError> {
+    data.watched_ports
+        .lock()
+        .map_err(|_| "watch mutex poisoned".to_string())?
+        .entry(port)
+        .or_default();
+    if !data.watch_active.load(Ordering::SeqCst) {
+        spawn_port_watch(app, DEFAULT_WATCH_INTERVAL_MS);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn unwatch_port(port: u16, data: State<AppData>) -> Result<(), AppError> {
+    data.watched_ports
+        .lock()
+        .map_err(|_| "watch mutex poisoned".to_string())?
+        .remove(&port);
+    Ok(())
+}
+
+const GUARD_TRIGGERED_EVENT: &str = "guard-triggered";
+
+#[derive(Serialize, Clone)]
+#[cfg_attr(feature = "bindings", derive(TS))]
+pub struct GuardTriggered {
+    pub pid: u32,
+    pub process_name: String,
+    pub result: KillResult,
+}
+
+// Kill each non-protected PID that newly appears on a guarded port. Each PID
+// gets one attempt per port, so a kill that fails (access denied, high risk)
+// isn't retried on every cycle; the slate is wiped once the port is free.
+fn enforce_port_guards(app: &AppHandle, data: &AppData, ports: &[PortInfo]) {
+    let targets: Vec<(u32, u16, String)> = {
+        let Ok(mut guarded) = data.guarded_ports.lock() else {
+            return;
+        };
+        let mut targets = Vec::new();
+        for (port, attempted) in guarded.iter_mut() {
+            let holders: Vec<&PortInfo> = ports.iter().filter(|p| p.port == *port).collect();
+            if holders.is_empty() {
+                attempted.clear();
+                continue;
+            }
+            for holder in holders {
+                if !holder.is_protected && attempted.insert(holder.pid) {
+                    targets.push((holder.pid, *port, holder.process_name.clone()));
+                }
+            }
+        }
+        targets
+    };
+
+    // kill_pid re-checks protection and refuses High-risk targets, since
+    // nobody is around to confirm them.
+    for (pid, port, process_name) in targets {
+        let result = kill_pid(
+            data,
+            pid,
+            port,
+            process_name.clone(),
+            &KillOptions::default(),
+        );
+        let _ = app.emit(
+            GUARD_TRIGGERED_EVENT,
+            GuardTriggered {
+                pid,
+                process_name,
+                result,
+            },
+        );
+    }
+}
+
+// Enforcing needs the background refresh, so start it if the UI hasn't.
+#[tauri::command]
+fn add_port_guard(port: u16, app: AppHandle, data: State<AppData>) -> Result<(), AppError> {
+    data.guarded_ports
+        .lock()
+        .map_err(|_| "guard mutex poisoned".to_string())?
+        .entry(port)
+        .or_default();
+    if !data.watch_active.load(Ordering::SeqCst) {
+        spawn_port_watch(app, DEFAULT_WATCH_INTERVAL_MS);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn remove_port_guard(port: u16, data: State<AppData>) -> Result<(), AppError> {
+    data.guarded_ports
+        .lock()
+        .map_err(|_| "guard mutex poisoned".to_string())?
+        .remove(&port);
+    Ok(())
+}
+
+// A port we're squatting on. The listener lives on `thread`, which drops it
+// once `stop` is set.
+struct PortReservation {
+    stop: Arc<AtomicBool>,
+    thread: std::thread::JoinHandle<()>,
+}
+
+const RESERVATION_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// Hold `port` on 0.0.0.0 so nothing else can grab it, e.g. while setting
+// up the server that will use it. Unlike an OS-reserved range this is ours
+// to release at any time. Only IPv4 is held, and on Windows a process
+// running as the same user can still bind a specific address (127.0.0.1,
+// a LAN IP) on top of the wildcard; the returned message says so. Binding
+// the wildcard may trigger a Windows Firewall prompt the first time.
+#[tauri::command]
+fn reserve_port(port: u16, data: State<AppData>) -> Result<String, AppError> {
+    let mut reserved = data
+        .reserved_ports
+        .lock()
+        .map_err(|_| "reservation mutex poisoned".to_string())?;
+    if reserved.contains_key(&port) {
+        return Err(AppError::InvalidInput(format!(
+            "Port {} is already reserved",
+            port
+        )));
+    }
+    let listener =
+        TcpListener::bind((Ipv4Addr::UNSPECIFIED, port)).map_err(|e| match e.kind() {
+            std::io::ErrorKind::AddrInUse => {
+                AppError::InvalidInput(format!("Port {} is already in use", port))
+            }
+            _ => AppError::from(e),
+        })?;
+    listener.set_nonblocking(true)?;
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread = {
+        let stop = stop.clone();
+        std::thread::spawn(move || {
+            // Connections are accepted and hung up on straight away, rather
+            // than left waiting in the backlog.
+            while !stop.load(Ordering::SeqCst) {
+                if listener.accept().is_err() {
+                    std::thread::sleep(RESERVATION_POLL_INTERVAL);
+                }
+            }
+        })
+    };
+    reserved.insert(port, PortReservation { stop, thread });
+    data.log("info", format!("Reserved port {}", port));
+    Ok(if cfg!(windows) {
+        format!(
+            "Reserved TCP port {} on all IPv4 addresses. Windows still lets \
+             programs running as you bind it on a specific address or IPv6.",
+            port
+        )
+    } else {
+        format!(
+            "Reserved TCP port {} on all IPv4 addresses. IPv6-only sockets \
+             can still bind it.",
+            port
+        )
+    })
+}
+
+#[tauri::command]
+fn release_port(port: u16, data: State<AppData>) -> Result<(), AppError> {
+    let reservation = data
+        .reserved_ports
+        .lock()
+        .map_err(|_| "reservation mutex poisoned".to_string())?
+        .remove(&port)
+        .ok_or_else(|| AppError::NotFound(format!("Port {} is not reserved", port)))?;
+    reservation.stop.store(true, Ordering::SeqCst);
+    // The listener is dropped when the thread exits; wait so the port is
+    // free by the time we return.
+    let _ = reservation.thread.join();
+    data.log("info", format!("Released port {}", port));
+    Ok(())
+}
+
+// RFC 4180 quoting: wrap in quotes when the field contains a delimiter,
+// quote or newline, doubling any embedded quotes.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn ports_to_csv(ports: &[PortInfo]) -> String {
+    let mut out =
+        String::from("port,protocol,pid,process_name,process_path,local_address,is_protected\n");
+    for p in ports {
+        out.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            p.port,
+            csv_field(&p.protocol),
+            p.pid,
+            csv_field(&p.process_name),
+            csv_field(&p.process_path),
+            csv_field(&p.local_address),
+            p.is_protected
+        ));
+    }
+    out
+}
+
+// A literal `|` would end the table cell early.
+fn markdown_cell(value: &str) -> String {
+    value.replace('|', "\\|")
+}
+
+fn ports_to_markdown(ports: &[PortInfo]) -> String {
+    let mut out =
+        String::from("| Port | Proto | PID | Process | Address |\n|---|---|---|---|---|\n");
+    for p in ports {
+        out.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            p.port,
+            p.protocol,
+            p.pid,
+            markdown_cell(&p.process_name),
+            markdown_cell(&p.local_address)
+        ));
+    }
+    out
+}
+
+// Current listeners as a GitHub-flavored markdown table, for pasting into
+// chat or an issue.
+#[tauri::command]
+fn export_ports_markdown(data: State<AppData>) -> Result<String, AppError> {
+    let state = collect_ports(&data, &listening_states(), &ScanOptions::default())?;
+    Ok(ports_to_markdown(&state.ports))
+}
+
+#[tauri::command]
+fn export_ports(format: String, path: String, data: State<AppData>) -> Result<(), AppError> {
+    let format = format.to_lowercase();
+    if format != "csv" && format != "json" {
+        return Err(AppError::InvalidInput(format!(
+            "Unsupported export format \"{}\" (expected \"csv\" or \"json\")",
+            format
+        )));
+    }
+
+    let state = collect_ports(&data, &listening_states(), &ScanOptions::default())?;
+    let contents = if format == "csv" {
+        ports_to_csv(&state.ports)
+    } else {
+        serde_json::to_string_pretty(&state).map_err(|e| e.to_string())?
+    };
+
+    fs::write(&path, contents).map_err(|e| AppError::Io(format!("Failed to write {}: {}", path, e)))
+}
+
+// Sockets owned by `pid`, as (listening ports, all connections).
+fn process_sockets(pid: u32) -> Result<(Vec<u16>, Vec<ConnectionInfo>), String> {
+    let af_flags = AddressFamilyFlags::IPV4 | AddressFamilyFlags::IPV6;
+    let proto_flags = ProtocolFlags::TCP | ProtocolFlags::UDP;
+    let sockets = get_sockets_info(af_flags, proto_flags).map_err(|e| e.to_string())?;
+
+    let mut listening: Vec<u16> = Vec::new();
+    let mut connections = Vec::new();
+    for socket in sockets
+        .into_iter()
+        .filter(|s| s.associated_pids.contains(&pid))
+    {
+        let info = match &socket.protocol_socket_info {
+            ProtocolSocketInfo::Tcp(tcp) => {
+                let is_listen = tcp.state == netstat2::TcpState::Listen;
+                if is_listen {
+                    listening.push(tcp.local_port);
+                }
+                ConnectionInfo {
+                    protocol: "TCP".to_string(),
+                    local_address: tcp.local_addr.to_string(),
+                    local_port: tcp.local_port,
+                    remote_address: if is_listen {
+                        None
+                    } else {
+                        Some(SocketAddr::new(tcp.remote_addr, tcp.remote_port).to_string())
+                    },
+                    state: tcp_state_name(&tcp.state).to_string(),
+                }
+            }
+            ProtocolSocketInfo::Udp(udp) => {
+                listening.push(udp.local_port);
+                ConnectionInfo {
+                    protocol: "UDP".to_string(),
+                    local_address: udp.local_addr.to_string(),
+                    local_port: udp.local_port,
+                    remote_address: None,
+                    state: UDP_STATE.to_string(),
+                }
+            }
+        };
+        connections.push(info);
+    }
+    listening.sort_unstable();
+    listening.dedup();
+    Ok((listening, connections))
+}
+
+#[tauri::command]
+fn get_process_details(pid: u32, data: State<AppData>) -> Result<ProcessDetails, AppError> {
+    // Enumerate sockets before taking the System lock; it doesn't need it.
+    // A failed enumeration just leaves the network fields empty.
+    let (listening_ports, connections) = process_sockets(pid).unwrap_or_default();
+
+    let mut system = data
+        .system
+        .lock()
+        .map_err(|_| "system mutex poisoned".to_string())?;
+    let sys_pid = Pid::from_u32(pid);
+
+    // sysinfo computes CPU as a delta between two refreshes of the same
+    // process. If this PID isn't in the shared snapshot yet there is no
+    // previous sample, so take one, wait out the minimum interval (without
+    // holding the lock), and let the refresh below produce a real reading.
+    if system.process(sys_pid).is_none() {
+        system.refresh_processes(ProcessesToUpdate::Some(&[sys_pid]));
+        drop(system);
+        std::thread::sleep(MINIMUM_CPU_UPDATE_INTERVAL);
+        system = data
+            .system
+            .lock()
+            .map_err(|_| "system mutex poisoned".to_string())?;
+    }
+
+    // Only refresh the target PID — refreshing every process on the machine
+    // every 3 s while the details panel is open is wasteful. The background
+    // watcher's periodic full refresh keeps the rest of the snapshot fresh
+    // enough for the children-discovery scan below.
+    system.refresh_processes(ProcessesToUpdate::Some(&[sys_pid]));
+
+    if let Some(process) = system.process(sys_pid) {
+        let name = process.name().to_string_lossy().to_string();
+        let path = process
+            .exe()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let memory_bytes = process.memory();
+        let virtual_memory_bytes = process.virtual_memory();
+        let cpu_percent = process.cpu_usage();
+        let cmd: Vec<String> = process
+            .cmd()
+            .iter()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect();
+
+        let parent_pid = process.parent().map(|p| p.as_u32());
+        let parent_name = parent_pid.map(|ppid| get_process_info(&system, ppid).0);
+        let disk_usage = process.disk_usage();
+        let run_duration_secs = unix_now().saturating_sub(process.start_time());
+
+        // Find child processes
+        let children: Vec<u32> = system
+            .processes()
+            .iter()
+            .filter_map(|(child_pid, child_proc)| {
+                if child_proc.parent() == Some(sys_pid) {
+                    Some(child_pid.as_u32())
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        let mut details = ProcessDetails {
+            pid,
+            name,
+            path,
+            memory_bytes,
+            virtual_memory_bytes,
+            memory_human: format_bytes(memory_bytes),
+            cpu_percent,
+            children,
+            cmd,
+            is_suspended: data
+                .suspended
+                .lock()
+                .map(|set| set.contains(&pid))
+                .unwrap_or(false),
+            parent_pid,
+            parent_name,
+            disk_read_bytes: disk_usage.total_read_bytes,
+            disk_written_bytes: disk_usage.total_written_bytes,
+            run_duration_secs,
+            handle_count: platform::handle_count(pid),
+            listening_ports,
+            connections,
+            signature: None,
+        };
+        // Checking a signature reads the whole file; don't hold up other
+        // commands waiting on the System lock meanwhile.
+        drop(system);
+        details.signature = cached_signature(&data, &details.path);
+        Ok(details)
+    } else {
+        Err(AppError::NotFound(format!("Process {} not found", pid)))
+    }
+}
+
+// The details panel refreshes every few seconds; only re-check a binary's
+// signature when its modification time changes.
+fn cached_signature(data: &AppData, path: &str) -> Option<platform::SignatureInfo> {
+    if path.is_empty() {
+        return None;
+    }
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok();
+    let key = (PathBuf::from(path), modified);
+    if let Some(hit) = data.signatures.lock().ok()?.get(&key) {
+        return hit.clone();
+    }
+    let info = platform::signature(Path::new(path));
+    if let Ok(mut cache) = data.signatures.lock() {
+        cache.insert(key, info.clone());
+    }
+    info
+}
+
+// The target's environment variables in block order, e.g. to see which PORT
+// a dev server was started with. Nothing is redacted; the UI filters.
+#[tauri::command]
+fn get_process_environment(pid: u32) -> Result<Vec<(String, String)>, AppError> {
+    platform::process_environment(pid)
+}
+
+// Visible window titles of `pid`, the clearest clue to what an opaque GUI
+// process is: "My App - Dev Server" rather than electron.exe. Empty for
+// console and background processes, and on Linux.
+#[tauri::command]
+fn get_process_windows(pid: u32) -> Vec<String> {
+    platform::window_titles(pid)
+}
+
+// Guards against a parent table that loops back on itself (PID reuse).
+const MAX_ANCESTRY_DEPTH: usize = 64;
+
+// `pid` and every ancestor, child first, up to the root or the first parent
+// that no longer exists (shell -> npm -> node -> worker reads bottom-up).
+#[tauri::command]
+fn get_process_ancestry(
+    pid: u32,
+    data: State<AppData>,
+) -> Result<Vec<ProcessDetailsLite>, AppError> {
+    let mut system = data
+        .system
+        .lock()
+        .map_err(|_| "system mutex poisoned".to_string())?;
+    system.refresh_processes(ProcessesToUpdate::All);
+
+    let mut chain = Vec::new();
+    let mut visited = HashSet::new();
+    let mut current = Some(Pid::from_u32(pid));
+    while let Some(sys_pid) = current {
+        if chain.len() == MAX_ANCESTRY_DEPTH || !visited.insert(sys_pid) {
+            break;
+        }
+        let Some(process) = system.process(sys_pid) else {
+            break;
+        };
+        chain.push(ProcessDetailsLite {
+            pid: sys_pid.as_u32(),
+            name: process.name().to_string_lossy().to_string(),
+            path: process
+                .exe()
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        });
+        // System / init is the root of every tree.
+        if PROTECTED_PIDS.contains(&sys_pid.as_u32()) {
+            break;
+        }
+        current = process.parent();
+    }
+
+    if chain.is_empty() {
+        return Err(AppError::NotFound(format!("Process {} not found", pid)));
+    }
+    Ok(chain)
+}
+
+#[tauri::command]
+fn open_task_manager() -> Result<(), AppError> {
+    platform::open_task_manager().map_err(AppError::from)
+}
+
+#[tauri::command]
+fn open_process_location(path: String) -> Result<(), AppError> {
+    if path.trim().is_empty() {
+        return Err(AppError::InvalidInput(
+            "No executable path available for this process".to_string(),
+        ));
+    }
+    let path = Path::new(&path);
+    if !path.exists() {
+        return Err(AppError::NotFound(format!(
+            "{} does not exist",
+            path.display()
+        )));
+    }
+    platform::reveal_in_file_manager(path).map_err(AppError::from)
+}
+
+// Shell-ready equivalent of a kill, for pasting into a script or a chat.
+// `shell` is "cmd" (taskkill), "powershell" or "sh"; defaults to the native
+// one for this platform.
+#[tauri::command]
+fn get_kill_command(pid: u32, shell: Option<String>) -> Result<String, AppError> {
+    let default_shell = if cfg!(windows) { "cmd" } else { "sh" };
+    match shell
+        .as_deref()
+        .unwrap_or(default_shell)
+        .to_lowercase()
+        .as_str()
+    {
+        "cmd" => Ok(format!("taskkill /F /PID {}", pid)),
+        "powershell" => Ok(format!("Stop-Process -Id {} -Force", pid)),
+        "sh" => Ok(format!("kill -9 {}", pid)),
+        other => Err(AppError::InvalidInput(format!("Unknown shell: {}", other))),
+    }
+}
+
+#[tauri::command]
+fn copy_to_clipboard(text: String, app: AppHandle) -> Result<(), AppError> {
+    app.clipboard()
+        .write_text(text)
+        .map_err(|e| AppError::Other(e.to_string()))
+}
+
+#[tauri::command]
+fn kill_process(
+    pid: u32,
+    port: u16,
+    process_name: String,
+    options: Option<KillOptions>,
+    app: AppHandle,
+    data: State<AppData>,
+) -> KillResult {
+    let result = kill_pid(&data, pid, port, process_name, &options.unwrap_or_default());
+    emit_kill_results(&app, std::slice::from_ref(&result));
+    result
+}
+
+// kill_process with taskkill's /T: one shell call that takes the process
+// and its children down, which copes with service trees better than
+// walking them ourselves (see kill_process_tree for that).
+#[tauri::command]
+fn kill_process_with_tree(
+    pid: u32,
+    port: u16,
+    process_name: String,
+    options: Option<KillOptions>,
+    app: AppHandle,
+    data: State<AppData>,
+) -> KillResult {
+    let options = KillOptions {
+        tree: true,
+        ..options.unwrap_or_default()
+    };
+    let result = kill_pid(&data, pid, port, process_name, &options);
+    emit_kill_results(&app, std::slice::from_ref(&result));
+    result
+}
+
+const KILL_RESULT_EVENT: &str = "kill-result";
+
+// Push each result to the main window as a "kill-result" event so toasts can
+// surface kills the window didn't initiate (tray, API, bulk actions). The
+// commands still return the results to their direct callers.
+fn emit_kill_results(app: &AppHandle, results: &[KillResult]) {
+    for result in results {
+        let _ = app.emit_to("main", KILL_RESULT_EVENT, result);
+    }
+}
+
+// Per-call knobs for the kill path, passed from the frontend as `options`.
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+#[cfg_attr(feature = "bindings", derive(TS))]
+struct KillOptions {
+    // Run the protection checks and PID resolution but terminate nothing.
+    dry_run: bool,
+    // Required to kill targets classify_kill_risk rates High.
+    confirmed: bool,
+    // Exit code handed to TerminateProcess (Windows only); defaults to 1.
+    exit_code: Option<u32>,
+    // Retry with taskkill when TerminateProcess fails (Windows only).
+    allow_fallback: bool,
+    // Kill with `taskkill /F /T` so the child tree goes too (Windows only).
+    // Only the root is checked against the protected list. Being a shell-out,
+    // it's refused when allow_fallback is off.
+    tree: bool,
+}
+
+impl Default for KillOptions {
+    fn default() -> Self {
+        KillOptions {
+            dry_run: false,
+            confirmed: false,
+            exit_code: None,
+            allow_fallback: true,
+            tree: false,
+        }
+    }
+}
+
+// Guardrail below the hard protected list. High: binary lives in a system
+// directory. Medium: runs as a service or shows a window with unsaved work.
+// A PID with no process behind it is NotFound rather than any risk level,
+// so it never reaches platform::terminate.
+fn classify_kill_risk(data: &AppData, pid: u32) -> Result<KillRisk, AppError> {
+    let (path, parent_name) = {
+        let Ok(mut system) = data.system.lock() else {
+            return Ok(KillRisk::High);
+        };
+        let sys_pid = Pid::from_u32(pid);
+        system.refresh_processes(ProcessesToUpdate::Some(&[sys_pid]));
+        let Some(process) = system.process(sys_pid) else {
+            return Err(AppError::NotFound(format!("Process {} not found", pid)));
+        };
+        let path = process.exe().map(Path::to_path_buf);
+        let parent_pid = process.parent();
+        let parent_name = parent_pid.and_then(|ppid| {
+            system.refresh_processes(ProcessesToUpdate::Some(&[ppid]));
+            system
+                .process(ppid)
+                .map(|p| p.name().to_string_lossy().to_string())
+        });
+        (path, parent_name)
+    };
+
+    if path.as_deref().is_some_and(platform::is_system_path) {
+        return Ok(KillRisk::High);
+    }
+    if parent_name
+        .as_deref()
+        .is_some_and(platform::is_service_host)
+        || platform::has_unsaved_window(pid)
+    {
+        return Ok(KillRisk::Medium);
+    }
+    Ok(KillRisk::Low)
+}
+
+const KILL_RETRIES: u32 = 3;
+const KILL_RETRY_BACKOFF_MS: u64 = 100;
+
+// platform::terminate, retried with a linear backoff on failures the
+// platform marks transient. Also returns how many retries it took.
+fn terminate_with_retry(
+    data: &AppData,
+    pid: u32,
+    options: &KillOptions,
+) -> (Result<&'static str, AppError>, u32) {
+    let mut retries = 0;
+    loop {
+        let outcome = platform::terminate(
+            pid,
+            options.exit_code.unwrap_or(1),
+            options.allow_fallback,
+            options.tree,
+        );
+        match outcome {
+            Err(e) if retries < KILL_RETRIES && e.transient => {
+                retries += 1;
+                data.log(
+                    "warn",
+                    format!(
+                        "Kill of PID {} failed transiently (OS error {}); retrying",
+                        pid,
+                        e.os_code.unwrap_or_default()
+                    ),
+                );
+                std::thread::sleep(Duration::from_millis(
+                    KILL_RETRY_BACKOFF_MS * retries as u64,
+                ));
+            }
+            outcome => return (outcome.map_err(|e| e.error), retries),
+        }
+    }
+}
+
+fn kill_pid(
+    data: &AppData,
+    pid: u32,
+    port: u16,
+    process_name: String,
+    options: &KillOptions,
+) -> KillResult {
+    if data.is_protected(pid, &process_name) {
+        let message = format!("Cannot kill protected system process: {}", process_name);
+        return KillResult {
+            success: false,
+            message: message.clone(),
+            port,
+            needs_confirmation: false,
+            error: Some(AppError::Protected(message)),
+            method: String::new(),
+        };
+    }
+
+    let risk = match classify_kill_risk(data, pid) {
+        Ok(risk) => risk,
+        Err(e) => {
+            return KillResult {
+                success: false,
+                message: e.to_string(),
+                port,
+                needs_confirmation: false,
+                error: Some(e),
+                method: String::new(),
+            }
+        }
+    };
+    if !options.confirmed && risk == KillRisk::High {
+        return KillResult {
+            success: false,
+            message: format!(
+                "{} (PID {}) runs from a system directory; confirm to kill it",
+                process_name, pid
+            ),
+            port,
+            needs_confirmation: true,
+            error: None,
+            method: String::new(),
+        };
+    }
+
+    if options.dry_run {
+        return KillResult {
+            success: true,
+            message: format!("Would kill {} PID {} on port {}", process_name, pid, port),
+            port,
+            needs_confirmation: false,
+            error: None,
+            method: String::new(),
+        };
+    }
+
+    let (outcome, retries) = terminate_with_retry(data, pid, options);
+    let result = match outcome {
+        Ok(method) => KillResult {
+            success: true,
+            message: if retries == 0 {
+                format!("Port {} freed (killed {})", port, process_name)
+            } else {
+                format!(
+                    "Port {} freed (killed {} on attempt {})",
+                    port,
+                    process_name,
+                    retries + 1
+                )
+            },
+            port,
+            needs_confirmation: false,
+            error: None,
+            method: method.to_string(),
+        },
+        Err(e) => KillResult {
+            success: false,
+            message: e.to_string(),
+            port,
+            needs_confirmation: false,
+            error: Some(e),
+            method: String::new(),
+        },
+    };
+
+    data.log(
+        if result.success { "info" } else { "error" },
+        format!(
+            "Kill {} (PID {}) on port {}: {}",
+            process_name, pid, port, result.message
+        ),
+    );
+    record_kill(
+        data,
+        KillRecord {
+            timestamp: unix_now(),
+            pid,
+            port,
+            process_name,
+            success: result.success,
+        },
+    );
+    result
+}
+
+#[tauri::command]
+fn restart_process(
+    pid: u32,
+    port: u16,
+    process_name: String,
+    options: Option<KillOptions>,
+    data: State<AppData>,
+) -> Result<KillResult, AppError> {
+    let options = options.unwrap_or_default();
+    if data.is_protected(pid, &process_name) {
+        return Err(AppError::Protected(format!(
+            "Cannot restart protected system process: {}",
+            process_name
+        )));
+    }
+
+    // Capture everything needed to relaunch before the process is gone.
+    let (exe, args, cwd) = {
+        let mut system = data
+            .system
+            .lock()
+            .map_err(|_| "system mutex poisoned".to_string())?;
+        let sys_pid = Pid::from_u32(pid);
+        system.refresh_processes(ProcessesToUpdate::Some(&[sys_pid]));
+        let process = system
+            .process(sys_pid)
+            .ok_or_else(|| AppError::NotFound(format!("Process {} not found", pid)))?;
+        let exe = process
+            .exe()
+            .map(Path::to_path_buf)
+            .ok_or_else(|| format!("Cannot read the executable path of {}", process_name))?;
+        // argv[0] is the program itself; Command supplies its own.
+        let args: Vec<OsString> = process.cmd().iter().skip(1).cloned().collect();
+        (exe, args, process.cwd().map(Path::to_path_buf))
+    };
+
+    // A High-risk target comes back needs_confirmation until the caller
+    // retries with `confirmed` set, as with kill_process.
+    let mut result = kill_pid(&data, pid, port, process_name.clone(), &options);
+    if !result.success {
+        return Ok(result);
+    }
+    if options.dry_run {
+        result.message = format!(
+            "Would restart {} PID {} on port {}",
+            process_name, pid, port
+        );
+        return Ok(result);
+    }
+
+    // Give the OS a moment to release the port before the new instance binds.
+    platform::wait_for_exit(pid, DEFAULT_GRACEFUL_TIMEOUT_MS);
+
+    let mut command = std::process::Command::new(&exe);
+    command.args(&args);
+    if let Some(dir) = cwd {
+        command.current_dir(dir);
+    }
+    match command.spawn() {
+        Ok(mut child) => {
+            result.message = format!(
+                "Restarted {} on port {} (new PID {})",
+                process_name,
+                port,
+                child.id()
+            );
+            // Reap it when it exits, or on Unix it lingers as a zombie
+            // under us that still looks alive to a later kill.
+            std::thread::spawn(move || {
+                let _ = child.wait();
+            });
+        }
+        Err(e) => {
+            result.message = format!("Killed {} but failed to restart it: {}", process_name, e);
+        }
+    }
+    Ok(result)
+}
+
+// taskkill spawns dominate bulk kills, so run a few at once.
+const BULK_KILL_WORKERS: usize = 4;
+
+#[tauri::command]
+fn kill_processes(
+    targets: Vec<(u32, u16, String)>,
+    options: Option<KillOptions>,
+    app: AppHandle,
+    data: State<AppData>,
+) -> Vec<KillResult> {
+    let results = kill_targets(&data, &targets, &options.unwrap_or_default());
+    emit_kill_results(&app, &results);
+    results
+}
+
+fn kill_targets(
+    data: &AppData,
+    targets: &[(u32, u16, String)],
+    options: &KillOptions,
+) -> Vec<KillResult> {
+    let workers = BULK_KILL_WORKERS.min(targets.len()).max(1);
+
+    // Worker `w` takes every `workers`-th target; results are tagged with
+    // their input index so the output order matches the request.
+    let indexed: Vec<(usize, KillResult)> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|w| {
+                scope.spawn(move || {
+                    targets
+                        .iter()
+                        .enumerate()
+                        .skip(w)
+                        .step_by(workers)
+                        .map(|(i, (pid, port, name))| {
+                            (i, kill_pid(data, *pid, *port, name.clone(), options))
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap_or_default())
+            .collect()
+    });
+
+    // A panicked worker loses its slots; report those as failures rather than
+    // shifting later results out of alignment.
+    let mut slots: Vec<Option<KillResult>> = vec![None; targets.len()];
+    for (i, result) in indexed {
+        slots[i] = Some(result);
+    }
+    slots
+        .into_iter()
+        .zip(targets)
+        .map(|(slot, (_, port, _))| {
+            slot.unwrap_or_else(|| KillResult {
+                success: false,
+                message: "Kill worker crashed".to_string(),
+                port: *port,
+                needs_confirmation: false,
+                error: None,
+                method: String::new(),
+            })
+        })
+        .collect()
+}
+
+const KILL_HISTORY_FILE: &str = "kill_history.jsonl";
+const KILL_HISTORY_MAX: usize = 500;
+
+// Append to the in-memory log and its newline-delimited JSON mirror. The file
+// is only rewritten when the cap trims old entries; otherwise we append a
+// single line. Persistence is best-effort — a failed write never fails a kill.
+fn record_kill(data: &AppData, record: KillRecord) {
+    let Ok(mut history) = data.kill_history.lock() else {
+        return;
+    };
+    history.push(record);
+
+    let Some(path) = data.history_path.get() else {
+        return;
+    };
+    if history.len() > KILL_HISTORY_MAX {
+        let excess = history.len() - KILL_HISTORY_MAX;
+        history.drain(..excess);
+        let body: String = history
+            .iter()
+            .filter_map(|r| serde_json::to_string(r).ok())
+            .map(|line| line + "\n")
+            .collect();
+        let _ = fs::write(path, body);
+    } else if let (Some(last), Ok(mut file)) = (
+        history.last(),
+        fs::OpenOptions::new().create(true).append(true).open(path),
+    ) {
+        if let Ok(line) = serde_json::to_string(last) {
+            let _ = writeln!(file, "{}", line);
+        }
+    }
+}
+
+fn load_kill_history(path: &Path) -> Vec<KillRecord> {
+    let Ok(raw) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let mut records: Vec<KillRecord> = raw
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    if records.len() > KILL_HISTORY_MAX {
+        records.drain(..records.len() - KILL_HISTORY_MAX);
+    }
+    records
+}
+
+#[tauri::command]
+fn get_kill_history(data: State<AppData>) -> Result<Vec<KillRecord>, AppError> {
+    data.kill_history
+        .lock()
+        .map(|history| history.clone())
+        .map_err(|_| AppError::Other("history mutex poisoned".to_string()))
+}
+
+#[tauri::command]
+fn clear_kill_history(data: State<AppData>) -> Result<(), AppError> {
+    let mut history = data
+        .kill_history
+        .lock()
+        .map_err(|_| "history mutex poisoned".to_string())?;
+    history.clear();
+    if let Some(path) = data.history_path.get() {
+        match fs::remove_file(path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(())
+}
+
+// Recent log entries, oldest first. `level` keeps only that level
+// ("info", "warn" or "error").
+#[tauri::command]
+fn get_logs(level: Option<String>, data: State<AppData>) -> Vec<LogEntry> {
+    let Ok(logs) = data.logs.lock() else {
+        return Vec::new();
+    };
+    logs.iter()
+        .filter(|entry| {
+            level
+                .as_deref()
+                .is_none_or(|level| entry.level.eq_ignore_ascii_case(level))
+        })
+        .cloned()
+        .collect()
+}
+
+#[tauri::command]
+fn clear_logs(data: State<AppData>) {
+    if let Ok(mut logs) = data.logs.lock() {
+        logs.clear();
+    }
+}
+
+// Collect every PID with a socket bound to `port`, optionally narrowed to
+// "TCP" or "UDP". Order follows the socket table; duplicates are dropped.
+fn pids_on_port(port: u16, protocol: Option<&str>) -> Result<Vec<u32>, AppError> {
+    let af_flags = AddressFamilyFlags::IPV4 | AddressFamilyFlags::IPV6;
+    let proto_flags = match protocol.map(|p| p.to_uppercase()).as_deref() {
+        Some("TCP") | Some("TCP6") => ProtocolFlags::TCP,
+        Some("UDP") | Some("UDP6") => ProtocolFlags::UDP,
+        Some(other) => {
+            return Err(AppError::InvalidInput(format!(
+                "Unknown protocol: {}",
+                other
+            )))
+        }
+        None => ProtocolFlags::TCP | ProtocolFlags::UDP,
+    };
+
+    let sockets = get_sockets_info(af_flags, proto_flags).map_err(|e| e.to_string())?;
+
+    let mut pids: Vec<u32> = Vec::new();
+    for socket in sockets {
+        let local_port = match &socket.protocol_socket_info {
+            ProtocolSocketInfo::Tcp(tcp) => tcp.local_port,
+            ProtocolSocketInfo::Udp(udp) => udp.local_port,
+        };
+        if local_port != port {
+            continue;
+        }
+        for pid in socket.associated_pids {
+            if !pids.contains(&pid) {
+                pids.push(pid);
+            }
+        }
+    }
+    Ok(pids)
+}
+
+#[tauri::command]
+fn kill_by_port(
+    port: u16,
+    protocol: Option<String>,
+    options: Option<KillOptions>,
+    app: AppHandle,
+    data: State<AppData>,
+) -> Vec<KillResult> {
+    let results = kill_port(
+        &data,
+        port,
+        protocol.as_deref(),
+        &options.unwrap_or_default(),
+    );
+    emit_kill_results(&app, &results);
+    results
+}
+
+fn kill_port(
+    data: &AppData,
+    port: u16,
+    protocol: Option<&str>,
+    options: &KillOptions,
+) -> Vec<KillResult> {
+    let pids = match pids_on_port(port, protocol) {
+        Ok(pids) => pids,
+        Err(e) => {
+            return vec![KillResult {
+                success: false,
+                message: format!("Failed to enumerate sockets: {}", e),
+                port,
+                needs_confirmation: false,
+                error: None,
+                method: String::new(),
+            }]
+        }
+    };
+
+    // Nothing bound — the caller treats an empty vec as "already free".
+    if pids.is_empty() {
+        return Vec::new();
+    }
+
+    // Resolve names up front and release the lock before terminating, since
+    // taskkill can take a while.
+    let targets: Vec<(u32, String)> = {
+        let mut system = match data.system.lock() {
+            Ok(system) => system,
+            Err(_) => {
+                return vec![KillResult {
+                    success: false,
+                    message: "system mutex poisoned".to_string(),
+                    port,
+                    needs_confirmation: false,
+                    error: None,
+                    method: String::new(),
+                }]
+            }
+        };
+        let sys_pids: Vec<Pid> = pids.iter().map(|&p| Pid::from_u32(p)).collect();
+        system.refresh_processes(ProcessesToUpdate::Some(&sys_pids));
+        pids.iter()
+            .map(|&pid| (pid, get_process_info(&system, pid).0))
+            .collect()
+    };
+
+    // A socket can be shared by several PIDs. When a killable non-host
+    // process is among them it's the real binding owner, so co-owning
+    // service hosts are left alone instead of being terminated alongside.
+    let has_owner = targets.len() > 1
+        && targets
+            .iter()
+            .any(|(pid, name)| !data.is_protected(*pid, name) && !is_shared_host(name));
+
+    targets
+        .into_iter()
+        .map(|(pid, process_name)| {
+            if data.is_protected(pid, &process_name) {
+                KillResult {
+                    success: false,
+                    message: format!(
+                        "Skipped protected system process: {} (PID {})",
+                        process_name, pid
+                    ),
+                    port,
+                    needs_confirmation: false,
+                    error: None,
+                    method: String::new(),
+                }
+            } else if has_owner && is_shared_host(&process_name) {
+                KillResult {
+                    success: false,
+                    message: format!(
+                        "Skipped shared host {} (PID {}); another process owns port {}",
+                        process_name, pid, port
+                    ),
+                    port,
+                    needs_confirmation: false,
+                    error: None,
+                    method: String::new(),
+                }
+            } else {
+                kill_pid(data, pid, port, process_name, options)
+            }
+        })
+        .collect()
+}
+
+// Two instances of the same server racing for a port: keep one and kill the
+// rest. `keep` is "newest" or "oldest" by process start time. Protected
+// processes are left out entirely; the survivor gets a "Kept ..." entry
+// alongside the kill results. `options` apply to every kill, so High-risk
+// duplicates can be confirmed or the whole resolution dry-run.
+#[tauri::command]
+fn resolve_port_conflict(
+    port: u16,
+    keep: String,
+    options: Option<KillOptions>,
+    app: AppHandle,
+    data: State<AppData>,
+) -> Result<Vec<KillResult>, AppError> {
+    let options = options.unwrap_or_default();
+    let keep_newest = match keep.to_lowercase().as_str() {
+        "newest" => true,
+        "oldest" => false,
+        other => {
+            return Err(AppError::InvalidInput(format!(
+                "Unknown policy \"{}\" (expected newest or oldest)",
+                other
+            )))
+        }
+    };
+
+    let pids = pids_on_port(port, None)?;
+    // (start time, PID, name), oldest first; PIDs break ties. A PID that
+    // has exited since the socket scan is dropped, not given start time 0,
+    // which would make it the "oldest" survivor.
+    let mut candidates: Vec<(u64, u32, String)> = {
+        let mut system = data
+            .system
+            .lock()
+            .map_err(|_| "system mutex poisoned".to_string())?;
+        let sys_pids: Vec<Pid> = pids.iter().map(|&p| Pid::from_u32(p)).collect();
+        system.refresh_processes(ProcessesToUpdate::Some(&sys_pids));
+        pids.iter()
+            .filter_map(|&pid| {
+                let start = system.process(Pid::from_u32(pid))?.start_time();
+                Some((start, pid, get_process_info(&system, pid).0))
+            })
+            .filter(|(_, pid, name)| !data.is_protected(*pid, name))
+            .collect()
+    };
+    candidates.sort_unstable_by_key(|(start, pid, _)| (*start, *pid));
+
+    let survivor = if keep_newest {
+        candidates.pop()
+    } else if candidates.is_empty() {
+        None
+    } else {
+        Some(candidates.remove(0))
+    };
+    let Some((_, kept_pid, kept_name)) = survivor else {
+        return Ok(Vec::new());
+    };
+
+    let mut results: Vec<KillResult> = candidates
+        .into_iter()
+        .map(|(_, pid, name)| kill_pid(&data, pid, port, name, &options))
+        .collect();
+    emit_kill_results(&app, &results);
+    results.push(KillResult {
+        success: false,
+        message: format!(
+            "Kept {} (PID {}), the {} process on port {}",
+            kept_name,
+            kept_pid,
+            if keep_newest { "newest" } else { "oldest" },
+            port
+        ),
+        port,
+        needs_confirmation: false,
+        error: None,
+        method: String::new(),
+    });
+    Ok(results)
+}
+
+// Kill every process whose name matches `name` (case-insensitive; exact or
+// substring). One result per PID, carrying the first port it held and
+// listing all of them in the message.
+#[tauri::command]
+fn kill_by_name(
+    name: String,
+    exact: bool,
+    options: Option<KillOptions>,
+    app: AppHandle,
+    data: State<AppData>,
+) -> Vec<KillResult> {
+    let results = kill_matching_name(&data, &name, exact, &options.unwrap_or_default());
+    emit_kill_results(&app, &results);
+    results
+}
+
+fn kill_matching_name(
+    data: &AppData,
+    name: &str,
+    exact: bool,
+    options: &KillOptions,
+) -> Vec<KillResult> {
+    let needle = name.trim().to_lowercase();
+    if needle.is_empty() {
+        // A substring match on "" would select every process.
+        return vec![KillResult {
+            success: false,
+            message: "Process name must not be empty".to_string(),
+            port: 0,
+            needs_confirmation: false,
+            error: None,
+            method: String::new(),
+        }];
+    }
+
+    let af_flags = AddressFamilyFlags::IPV4 | AddressFamilyFlags::IPV6;
+    let proto_flags = ProtocolFlags::TCP | ProtocolFlags::UDP;
+    let mut ports_of: HashMap<u32, Vec<u16>> = HashMap::new();
+    if let Ok(sockets) = get_sockets_info(af_flags, proto_flags) {
+        for socket in sockets {
+            let local_port = socket.local_port();
+            for pid in socket.associated_pids {
+                ports_of.entry(pid).or_default().push(local_port);
+            }
+        }
+    }
+
+    let own_pid = std::process::id();
+    let targets: Vec<(u32, String)> = {
+        let mut system = match data.system.lock() {
+            Ok(system) => system,
+            Err(_) => {
+                return vec![KillResult {
+                    success: false,
+                    message: "system mutex poisoned".to_string(),
+                    port: 0,
+                    needs_confirmation: false,
+                    error: None,
+                    method: String::new(),
+                }]
+            }
+        };
+        system.refresh_processes(ProcessesToUpdate::All);
+        system
+            .processes()
+            .iter()
+            .map(|(pid, process)| (pid.as_u32(), process.name().to_string_lossy().to_string()))
+            .filter(|(pid, process_name)| {
+                let lower = process_name.to_lowercase();
+                *pid != own_pid
+                    && if exact {
+                        lower == needle
+                    } else {
+                        lower.contains(&needle)
+                    }
+            })
+            .collect()
+    };
+
+    targets
+        .into_iter()
+        .map(|(pid, process_name)| {
+            let mut ports = ports_of.remove(&pid).unwrap_or_default();
+            ports.sort_unstable();
+            ports.dedup();
+            let port = ports.first().copied().unwrap_or(0);
+            if data.is_protected(pid, &process_name) {
+                return KillResult {
+                    success: false,
+                    message: format!(
+                        "Skipped protected system process: {} (PID {})",
+                        process_name, pid
+                    ),
+                    port,
+                    needs_confirmation: false,
+                    error: None,
+                    method: String::new(),
+                };
+            }
+            let mut result = kill_pid(data, pid, port, process_name.clone(), options);
+            if result.success && !options.dry_run {
+                let freed: Vec<String> = ports.iter().map(u16::to_string).collect();
+                result.message = if freed.is_empty() {
+                    format!("Killed {} (PID {})", process_name, pid)
+                } else {
+                    format!(
+                        "Killed {} (PID {}), freed {}",
+                        process_name,
+                        pid,
+                        freed.join(", ")
+                    )
+                };
+            }
+            result
+        })
+        .collect()
+}
+
+// The usual stale dev-server ports: Next/CRA, Vite, Tomcat/webpack, Angular,
+// Flask, Django.
+const DEFAULT_DEV_PORTS: &[u16] = &[3000, 5173, 8080, 4200, 5000, 8000];
+
+#[tauri::command]
+fn free_dev_ports(
+    ports: Option<Vec<u16>>,
+    app: AppHandle,
+    data: State<AppData>,
+) -> Vec<KillResult> {
+    let ports = ports.unwrap_or_else(|| DEFAULT_DEV_PORTS.to_vec());
+    let options = KillOptions::default();
+    let results: Vec<KillResult> = ports
+        .into_iter()
+        .map(|port| summarize_port_kill(port, kill_port(&data, port, None, &options)))
+        .collect();
+    emit_kill_results(&app, &results);
+    results
+}
+
+// How many occupied dev ports the tray menu offers to free.
+const TRAY_DEV_PORT_LIMIT: usize = 5;
+// Menu id prefix of those entries; the port follows, e.g. "free:3000".
+const TRAY_FREE_PREFIX: &str = "free:";
+
+fn tray_dev_ports(ports: &[PortInfo]) -> Vec<(u16, String)> {
+    DEFAULT_DEV_PORTS
+        .iter()
+        .filter_map(|&port| {
+            ports
+                .iter()
+                .find(|p| p.port == port && !p.is_protected)
+                .map(|p| (port, p.process_name.clone()))
+        })
+        .take(TRAY_DEV_PORT_LIMIT)
+        .collect()
+}
+
+// Show, one "Free <port> — <process>" entry per occupied dev port, Quit.
+fn build_tray_menu(
+    app: &AppHandle,
+    show: &MenuItem<Wry>,
+    entries: &[(u16, String)],
+) -> tauri::Result<Menu<Wry>> {
+    let menu = Menu::new(app)?;
+    menu.append(show)?;
+    if !entries.is_empty() {
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+        for (port, process_name) in entries {
+            menu.append(&MenuItem::with_id(
+                app,
+                format!("{}{}", TRAY_FREE_PREFIX, port),
+                format!("Free {} — {}", port, process_name),
+                true,
+                None::<&str>,
+            )?)?;
+        }
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+    }
+    menu.append(&MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?)?;
+    Ok(menu)
+}
+
+fn update_tray_menu(app: &AppHandle, ports: &[PortInfo]) {
+    let Some(items) = app.try_state::<TrayMenuItems>() else {
+        return;
+    };
+    let entries = tray_dev_ports(ports);
+    {
+        let Ok(mut current) = items.dev_ports.lock() else {
+            return;
+        };
+        if *current == entries {
+            return;
+        }
+        *current = entries.clone();
+    }
+    let Some(tray) = app.tray_by_id("main") else {
+        return;
+    };
+    if let Err(e) =
+        build_tray_menu(app, &items.show, &entries).and_then(|menu| tray.set_menu(Some(menu)))
+    {
+        app.state::<AppData>()
+            .log("warn", format!("Failed to rebuild the tray menu: {}", e));
+    }
+}
+
+// Switch the tray to the alert icon while any guarded or watched port is
+// held, naming the ports in the tooltip, and back once they're all free.
+fn update_tray_alert(app: &AppHandle, data: &AppData, ports: &[PortInfo]) {
+    let Some(items) = app.try_state::<TrayMenuItems>() else {
+        return;
+    };
+    let mut configured: HashSet<u16> = HashSet::new();
+    if let Ok(guarded) = data.guarded_ports.lock() {
+        configured.extend(guarded.keys());
+    }
+    if let Ok(watched) = data.watched_ports.lock() {
+        configured.extend(watched.keys());
+    }
+    let mut occupied: Vec<u16> = ports
+        .iter()
+        .map(|p| p.port)
+        .filter(|port| configured.contains(port))
+        .collect();
+    occupied.sort_unstable();
+    occupied.dedup();
+    {
+        let Ok(mut current) = items.alert_ports.lock() else {
+            return;
+        };
+        if *current == occupied {
+            return;
+        }
+        *current = occupied.clone();
+    }
+
+    let Some(tray) = app.tray_by_id("main") else {
+        return;
+    };
+    let (icon, tooltip) = if occupied.is_empty() {
+        (app.default_window_icon().cloned(), "PortKiller".to_string())
+    } else {
+        let list: Vec<String> = occupied.iter().map(u16::to_string).collect();
+        (
+            Some(tauri::include_image!("icons/tray-alert.png")),
+            format!(
+                "PortKiller: port{} {} in use",
+                if occupied.len() == 1 { "" } else { "s" },
+                list.join(", ")
+            ),
+        )
+    };
+    if let Err(e) = tray
+        .set_icon(icon)
+        .and_then(|_| tray.set_tooltip(Some(tooltip)))
+    {
+        data.log("warn", format!("Failed to update the tray icon: {}", e));
+    }
+}
+
+// Kill from the tray on a worker thread (termination can take seconds),
+// report it by notification since the window is usually hidden, then
+// refresh the menu so the freed port drops out.
+fn free_port_from_tray(app: &AppHandle, port: u16) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let data = app.state::<AppData>();
+        let result =
+            summarize_port_kill(port, kill_port(&data, port, None, &KillOptions::default()));
+        emit_kill_results(&app, std::slice::from_ref(&result));
+        let _ = app
+            .notification()
+            .builder()
+            .title("PortKiller")
+            .body(&result.message)
+            .show();
+        if let Ok(state) = collect_ports(&data, &listening_states(), &ScanOptions::default()) {
+            update_tray_menu(&app, &state.ports);
+        }
+    });
+}
+
+// Fold kill_port's per-PID results into one entry for the port.
+fn summarize_port_kill(port: u16, results: Vec<KillResult>) -> KillResult {
+    if results.is_empty() {
+        return KillResult {
+            success: true,
+            message: format!("Nothing to free on port {}", port),
+            port,
+            needs_confirmation: false,
+            error: None,
+            method: String::new(),
+        };
+    }
+    KillResult {
+        success: results.iter().all(|r| r.success),
+        message: results
+            .iter()
+            .map(|r| r.message.as_str())
+            .collect::<Vec<_>>()
+            .join("; "),
+        port,
+        needs_confirmation: results.iter().any(|r| r.needs_confirmation),
+        error: results.iter().find_map(|r| r.error.clone()),
+        method: results
+            .iter()
+            .map(|r| r.method.clone())
+            .find(|m| !m.is_empty())
+            .unwrap_or_default(),
+    }
+}
+
+const FREE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// Kill everything on `port`, then block until no listener is left (a dying
+// server can hold the socket for a moment after the kill returns). Returns
+// whether the port was free before `timeout_ms` ran out.
+#[tauri::command]
+async fn free_port_and_wait(port: u16, timeout_ms: u64, app: AppHandle) -> Result<bool, AppError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let data = app.state::<AppData>();
+        let results = kill_port(&data, port, None, &KillOptions::default());
+        emit_kill_results(&app, &results);
+        // Every kill failed (protected, access denied, ...): the port won't
+        // free itself, so don't sit out the timeout.
+        if !results.is_empty() && results.iter().all(|r| !r.success) {
+            return Ok(false);
+        }
+
+        let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+        loop {
+            if find_listener(&data, port, None)?.is_none() {
+                return Ok(true);
+            }
+            if Instant::now() >= deadline {
+                return Ok(false);
+            }
+            std::thread::sleep(FREE_POLL_INTERVAL);
+        }
+    })
+    .await
+    .map_err(|e| AppError::Other(e.to_string()))?
+}
+
+// Post-order walk of the descendant tree: every child appears before its
+// parent, so killing in order never leaves an orphan to re-bind the port.
+// `visited` guards against cycles in a malformed parent table (PID reuse can
+// make a process appear to be its own ancestor).
+fn collect_descendants(
+    children_of: &HashMap<u32, Vec<u32>>,
+    pid: u32,
+    visited: &mut HashSet<u32>,
+    out: &mut Vec<u32>,
+) {
+    if !visited.insert(pid) {
+        return;
+    }
+    if let Some(children) = children_of.get(&pid) {
+        for &child in children {
+            collect_descendants(children_of, child, visited, out);
+        }
+    }
+    out.push(pid);
+}
+
+// `timeout_ms` bounds the whole operation: once it's spent, a process still
+// being killed is reported as not confirmed (its kill carries on in the
+// background) and the rest are reported as not attempted.
+#[tauri::command]
+fn kill_process_tree(
+    pid: u32,
+    port: u16,
+    process_name: String,
+    options: Option<KillOptions>,
+    timeout_ms: Option<u64>,
+    app: AppHandle,
+) -> Vec<KillResult> {
+    let results = kill_tree(
+        &app,
+        pid,
+        port,
+        process_name,
+        &options.unwrap_or_default(),
+        timeout_ms,
+    );
+    emit_kill_results(&app, &results);
+    results
+}
+
+fn kill_tree(
+    app: &AppHandle,
+    pid: u32,
+    port: u16,
+    process_name: String,
+    options: &KillOptions,
+    timeout_ms: Option<u64>,
+) -> Vec<KillResult> {
+    let data = app.state::<AppData>().inner();
+    if data.is_protected(pid, &process_name) {
+        return vec![kill_pid(data, pid, port, process_name, options)];
+    }
+    let deadline = timeout_ms.map(|ms| (Instant::now() + Duration::from_millis(ms), ms));
+
+    // Snapshot the tree (leaves first, root last) and release the lock before
+    // terminating anything.
+    let targets: Vec<(u32, String)> = {
+        let mut system = match data.system.lock() {
+            Ok(system) => system,
+            Err(_) => {
+                return vec![KillResult {
+                    success: false,
+                    message: "system mutex poisoned".to_string(),
+                    port,
+                    needs_confirmation: false,
+                    error: None,
+                    method: String::new(),
+                }]
+            }
+        };
+        system.refresh_processes(ProcessesToUpdate::All);
+
+        let mut children_of: HashMap<u32, Vec<u32>> = HashMap::new();
+        for (child_pid, child_proc) in system.processes() {
+            if let Some(parent) = child_proc.parent() {
+                children_of
+                    .entry(parent.as_u32())
+                    .or_default()
+                    .push(child_pid.as_u32());
+            }
+        }
+
+        let mut order = Vec::new();
+        collect_descendants(&children_of, pid, &mut HashSet::new(), &mut order);
+        order
+            .into_iter()
+            .map(|p| {
+                let name = if p == pid {
+                    process_name.clone()
+                } else {
+                    get_process_info(&system, p).0
+                };
+                (p, name)
+            })
+            .collect()
+    };
+
+    targets
+        .into_iter()
+        .map(|(p, name)| {
+            if data.is_protected(p, &name) {
+                KillResult {
+                    success: false,
+                    message: format!("Skipped protected system process: {} (PID {})", name, p),
+                    port,
+                    needs_confirmation: false,
+                    error: None,
+                    method: String::new(),
+                }
+            } else if let Some((deadline, budget)) = deadline {
+                kill_pid_within(app, p, port, name, options, deadline, budget)
+            } else {
+                kill_pid(data, p, port, name, options)
+            }
+        })
+        .collect()
+}
+
+// kill_pid on a worker thread, waiting no later than `deadline` for it.
+// `budget_ms` is the tree's whole budget, for the messages.
+fn kill_pid_within(
+    app: &AppHandle,
+    pid: u32,
+    port: u16,
+    process_name: String,
+    options: &KillOptions,
+    deadline: Instant,
+    budget_ms: u64,
+) -> KillResult {
+    let unconfirmed = |message: String| KillResult {
+        success: false,
+        message: message.clone(),
+        port,
+        needs_confirmation: false,
+        error: Some(AppError::Other(message)),
+        method: String::new(),
+    };
+    let remaining = deadline.saturating_duration_since(Instant::now());
+    if remaining.is_zero() {
+        return unconfirmed(format!(
+            "Not attempted: {} (PID {}), the {} ms budget ran out",
+            process_name, pid, budget_ms
+        ));
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let worker_app = app.clone();
+    let worker_name = process_name.clone();
+    let worker_options = options.clone();
+    std::thread::spawn(move || {
+        let data = worker_app.state::<AppData>();
+        let _ = tx.send(kill_pid(&data, pid, port, worker_name, &worker_options));
+    });
+    rx.recv_timeout(remaining).unwrap_or_else(|_| {
+        unconfirmed(format!(
+            "Not confirmed: {} (PID {}) was still exiting when the {} ms budget ran out",
+            process_name, pid, budget_ms
+        ))
+    })
+}
+
+const DEFAULT_GRACEFUL_TIMEOUT_MS: u64 = 3000;
+
+#[tauri::command]
+fn suspend_process(pid: u32, data: State<AppData>) -> Result<(), AppError> {
+    let name = lookup_process_name(&data, pid)?;
+    if data.is_protected(pid, &name) {
+        return Err(AppError::Protected(format!(
+            "Cannot suspend protected system process: {}",
+            name
+        )));
+    }
+    platform::suspend(pid)?;
+    if let Ok(mut set) = data.suspended.lock() {
+        set.insert(pid);
+    }
+    Ok(())
+}
+
+// Gentler than a kill: deprioritize a runaway build instead of ending it.
+#[tauri::command]
+fn set_process_priority(pid: u32, priority: String, data: State<AppData>) -> Result<(), AppError> {
+    let level = platform::Priority::parse(&priority).ok_or_else(|| {
+        AppError::InvalidInput(format!(
+            "Unknown priority \"{}\" (expected idle, below_normal, normal, above_normal or high)",
+            priority
+        ))
+    })?;
+    let name = lookup_process_name(&data, pid)?;
+    if data.is_protected(pid, &name) {
+        return Err(AppError::Protected(format!(
+            "Cannot change priority of protected system process: {}",
+            name
+        )));
+    }
+    platform::set_priority(pid, level)
+}
+
+#[tauri::command]
+fn resume_process(pid: u32, data: State<AppData>) -> Result<(), AppError> {
+    let name = lookup_process_name(&data, pid)?;
+    if data.is_protected(pid, &name) {
+        return Err(AppError::Protected(format!(
+            "Cannot resume protected system process: {}",
+            name
+        )));
+    }
+    platform::resume(pid)?;
+    if let Ok(mut set) = data.suspended.lock() {
+        set.remove(&pid);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn kill_process_graceful(
+    pid: u32,
+    port: u16,
+    process_name: String,
+    timeout_ms: Option<u64>,
+    data: State<AppData>,
+) -> KillResult {
+    if data.is_protected(pid, &process_name) {
+        return kill_pid(&data, pid, port, process_name, &KillOptions::default());
+    }
+
+    let timeout_ms = timeout_ms.unwrap_or(DEFAULT_GRACEFUL_TIMEOUT_MS);
+
+    let Some(also_signalled) = platform::request_close(pid) else {
+        // Nothing to ask nicely (no windows and no console, e.g. a detached
+        // service) — skip straight to the force path instead of waiting out
+        // the full timeout.
+        let mut result = kill_pid(&data, pid, port, process_name, &KillOptions::default());
+        if result.success {
+            result.message = format!("{} (no window or console, force-killed)", result.message);
+        }
+        return result;
+    };
+    // Ctrl+Break goes to the whole console; say who else got it.
+    let also_note = if also_signalled.is_empty() {
+        String::new()
+    } else {
+        let pids: Vec<String> = also_signalled.iter().map(u32::to_string).collect();
+        format!("; Ctrl+Break also reached PID {}", pids.join(", "))
+    };
+
+    if platform::wait_for_exit(pid, timeout_ms) {
+        return KillResult {
+            success: true,
+            message: format!(
+                "Port {} freed ({} exited gracefully{})",
+                port, process_name, also_note
+            ),
+            port,
+            needs_confirmation: false,
+            error: None,
+            method: String::new(),
+        };
+    }
 
-            let (process_name, process_path) = get_process_info(&system, pid_u32);
-            let is_protected = is_protected_process(pid_u32, &process_name);
+    let mut result = kill_pid(&data, pid, port, process_name, &KillOptions::default());
+    if result.success {
+        result.message = format!(
+            "{} (force-killed after {} ms without closing{})",
+            result.message, timeout_ms, also_note
+        );
+    }
+    result
+}
 
-            ports.push(PortInfo {
-                pid: pid_u32,
-                port: local_port,
-                protocol: protocol.clone(),
-                process_name,
-                process_path,
-                is_protected,
-                local_address: local_addr.clone(),
-            });
+const PROTECTED_LIST_FILE: &str = "protected.json";
+const IGNORE_LIST_FILE: &str = "ignored.json";
+const FAVORITES_FILE: &str = "favorites.json";
+const GEOIP_FILE: &str = "GeoLite2-Country.mmdb";
+
+// Private, loopback and link-local peers have no meaningful country.
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip.to_canonical() {
+        IpAddr::V4(v4) => {
+            !(v4.is_private()
+                || v4.is_loopback()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast())
+        }
+        IpAddr::V6(v6) => {
+            let first = v6.segments()[0];
+            // fc00::/7 unique-local, fe80::/10 link-local.
+            !(v6.is_loopback()
+                || v6.is_unspecified()
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80)
         }
     }
+}
 
-    ports.sort_by_key(|p| p.port);
+fn lookup_country(reader: &maxminddb::Reader<Vec<u8>>, ip: IpAddr) -> Option<String> {
+    if !is_public_ip(ip) {
+        return None;
+    }
+    let record: maxminddb::geoip2::Country = reader.lookup(ip).ok()?;
+    record.country?.iso_code.map(str::to_string)
+}
 
-    let last_updated = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
+// A database the user imported lives in the config dir; a packager can also
+// ship one in the bundle's resources.
+fn load_geoip(app: &AppHandle) -> Option<maxminddb::Reader<Vec<u8>>> {
+    let candidates = [
+        config_file(app, GEOIP_FILE).ok(),
+        app.path().resource_dir().ok().map(|d| d.join(GEOIP_FILE)),
+    ];
+    candidates
+        .into_iter()
+        .flatten()
+        .find_map(|path| maxminddb::Reader::open_readfile(path).ok())
+}
+
+// Import a MaxMind-format Country database. It's copied into the config dir
+// so it survives restarts.
+#[tauri::command]
+fn set_geoip_database(path: String, app: AppHandle, data: State<AppData>) -> Result<(), AppError> {
+    let reader = maxminddb::Reader::open_readfile(&path)
+        .map_err(|e| format!("Not a valid GeoIP database: {}", e))?;
+    let target = config_file(&app, GEOIP_FILE)?;
+    if Path::new(&path) != target.as_path() {
+        fs::copy(&path, &target).map_err(|e| e.to_string())?;
+    }
+    *data
+        .geoip
+        .lock()
+        .map_err(|_| "geoip mutex poisoned".to_string())? = Some(reader);
+    Ok(())
+}
+
+// Resolve a file in the app config dir, creating the directory on first use.
+fn config_file(app: &AppHandle, name: &str) -> Result<PathBuf, String> {
+    let dir = app.path().app_config_dir().map_err(|e| e.to_string())?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir.join(name))
+}
+
+// Only user additions are persisted — the built-ins always come from
+// PROTECTED_PROCESSES so a stale file can never drop one.
+fn save_protected_list(app: &AppHandle, set: &HashSet<String>) -> Result<(), String> {
+    let mut custom: Vec<&String> = set
+        .iter()
+        .filter(|n| !PROTECTED_PROCESSES.contains(&n.as_str()))
+        .collect();
+    custom.sort();
+    let json = serde_json::to_string_pretty(&custom).map_err(|e| e.to_string())?;
+    fs::write(config_file(app, PROTECTED_LIST_FILE)?, json).map_err(|e| e.to_string())
+}
+
+// Process names from protected.json / ignored.json. Missing or malformed
+// files yield an empty list.
+fn load_name_list(path: &Path) -> Vec<String> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| serde_json::from_str::<Vec<String>>(&raw).ok())
         .unwrap_or_default()
-        .as_secs();
+}
 
-    Ok(AppState {
-        ports,
-        last_updated,
-        is_admin: data.is_admin,
-    })
+#[tauri::command]
+fn get_protected_list(data: State<AppData>) -> Result<Vec<String>, AppError> {
+    let set = data
+        .protected
+        .lock()
+        .map_err(|_| "protected mutex poisoned".to_string())?;
+    let mut list: Vec<String> = set.iter().cloned().collect();
+    list.sort();
+    Ok(list)
 }
 
 #[tauri::command]
-fn get_process_details(pid: u32, data: State<AppData>) -> Result<ProcessDetails, String> {
-    let mut system = data
-        .system
+fn add_protected(name: String, app: AppHandle, data: State<AppData>) -> Result<(), AppError> {
+    let name = name.trim().to_lowercase();
+    if name.is_empty() {
+        return Err(AppError::InvalidInput(
+            "Process name cannot be empty".to_string(),
+        ));
+    }
+    let mut set = data
+        .protected
         .lock()
-        .map_err(|_| "system mutex poisoned".to_string())?;
-    let sys_pid = Pid::from_u32(pid);
-    // Only refresh the target PID — refreshing every process on the machine
-    // every 3 s while the details panel is open is wasteful. The main poll
-    // (`get_listening_ports`) keeps the rest of the snapshot fresh enough for
-    // the children-discovery scan below.
-    system.refresh_processes(ProcessesToUpdate::Some(&[sys_pid]));
+        .map_err(|_| "protected mutex poisoned".to_string())?;
+    if set.insert(name) {
+        save_protected_list(&app, &set)?;
+    }
+    Ok(())
+}
 
-    if let Some(process) = system.process(sys_pid) {
-        let name = process.name().to_string_lossy().to_string();
-        let path = process
-            .exe()
-            .map(|p| p.to_string_lossy().to_string())
-            .unwrap_or_default();
-        let memory_bytes = process.memory();
-        let cpu_percent = process.cpu_usage();
+#[tauri::command]
+fn remove_protected(name: String, app: AppHandle, data: State<AppData>) -> Result<(), AppError> {
+    let name = name.trim().to_lowercase();
+    if PROTECTED_PROCESSES.contains(&name.as_str()) {
+        return Err(AppError::Protected(format!(
+            "{} is a built-in protected process",
+            name
+        )));
+    }
+    let mut set = data
+        .protected
+        .lock()
+        .map_err(|_| "protected mutex poisoned".to_string())?;
+    if set.remove(&name) {
+        save_protected_list(&app, &set)?;
+    }
+    Ok(())
+}
 
-        // Find child processes
-        let children: Vec<u32> = system
-            .processes()
-            .iter()
-            .filter_map(|(child_pid, child_proc)| {
-                if child_proc.parent() == Some(sys_pid) {
-                    Some(child_pid.as_u32())
-                } else {
-                    None
-                }
-            })
-            .collect();
+fn save_ignore_list(app: &AppHandle, set: &HashSet<String>) -> Result<(), String> {
+    let mut list: Vec<&String> = set.iter().collect();
+    list.sort();
+    let json = serde_json::to_string_pretty(&list).map_err(|e| e.to_string())?;
+    fs::write(config_file(app, IGNORE_LIST_FILE)?, json).map_err(|e| e.to_string())
+}
 
-        Ok(ProcessDetails {
-            pid,
-            name,
-            path,
-            memory_bytes,
-            cpu_percent,
-            children,
-        })
-    } else {
-        Err(format!("Process {} not found", pid))
+#[tauri::command]
+fn get_ignore_list(data: State<AppData>) -> Result<Vec<String>, AppError> {
+    let set = data
+        .ignored
+        .lock()
+        .map_err(|_| "ignore mutex poisoned".to_string())?;
+    let mut list: Vec<String> = set.iter().cloned().collect();
+    list.sort();
+    Ok(list)
+}
+
+#[tauri::command]
+fn add_to_ignore(name: String, app: AppHandle, data: State<AppData>) -> Result<(), AppError> {
+    let name = name.trim().to_lowercase();
+    if name.is_empty() {
+        return Err(AppError::InvalidInput(
+            "Process name cannot be empty".to_string(),
+        ));
+    }
+    let mut set = data
+        .ignored
+        .lock()
+        .map_err(|_| "ignore mutex poisoned".to_string())?;
+    if set.insert(name) {
+        save_ignore_list(&app, &set)?;
     }
+    Ok(())
 }
 
 #[tauri::command]
-fn open_task_manager() -> Result<(), String> {
-    use std::process::Command;
+fn remove_from_ignore(name: String, app: AppHandle, data: State<AppData>) -> Result<(), AppError> {
+    let name = name.trim().to_lowercase();
+    let mut set = data
+        .ignored
+        .lock()
+        .map_err(|_| "ignore mutex poisoned".to_string())?;
+    if set.remove(&name) {
+        save_ignore_list(&app, &set)?;
+    }
+    Ok(())
+}
 
-    Command::new("taskmgr.exe")
-        .creation_flags(0x08000000)
-        .spawn()
-        .map_err(|e| e.to_string())?;
+fn save_favorites(app: &AppHandle, set: &HashSet<String>) -> Result<(), String> {
+    let mut list: Vec<&String> = set.iter().collect();
+    list.sort();
+    let json = serde_json::to_string_pretty(&list).map_err(|e| e.to_string())?;
+    fs::write(config_file(app, FAVORITES_FILE)?, json).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_favorites(data: State<AppData>) -> Result<Vec<String>, AppError> {
+    let set = data
+        .favorites
+        .lock()
+        .map_err(|_| "favorites mutex poisoned".to_string())?;
+    let mut list: Vec<String> = set.iter().cloned().collect();
+    list.sort();
+    Ok(list)
+}
 
+#[tauri::command]
+fn add_favorite(name: String, app: AppHandle, data: State<AppData>) -> Result<(), AppError> {
+    let name = name.trim().to_lowercase();
+    if name.is_empty() {
+        return Err(AppError::InvalidInput(
+            "Process name cannot be empty".to_string(),
+        ));
+    }
+    let mut set = data
+        .favorites
+        .lock()
+        .map_err(|_| "favorites mutex poisoned".to_string())?;
+    if set.insert(name) {
+        save_favorites(&app, &set)?;
+    }
     Ok(())
 }
 
 #[tauri::command]
-fn kill_process(pid: u32, port: u16, process_name: String) -> KillResult {
-    if is_protected_process(pid, &process_name) {
-        return KillResult {
-            success: false,
-            message: format!("Cannot kill protected system process: {}", process_name),
-            port,
-        };
+fn remove_favorite(name: String, app: AppHandle, data: State<AppData>) -> Result<(), AppError> {
+    let name = name.trim().to_lowercase();
+    let mut set = data
+        .favorites
+        .lock()
+        .map_err(|_| "favorites mutex poisoned".to_string())?;
+    if set.remove(&name) {
+        save_favorites(&app, &set)?;
     }
+    Ok(())
+}
 
-    // First try Windows API
-    let api_result = unsafe {
-        let handle: Result<HANDLE, _> = OpenProcess(PROCESS_TERMINATE, false, pid);
+// Cheap elevation check for the UI, without a full port scan. Queries the
+// token each time rather than returning the cached startup value.
+#[tauri::command]
+fn check_admin() -> bool {
+    platform::is_running_as_admin()
+}
 
-        match handle {
-            Ok(h) => {
-                if h.is_invalid() {
-                    false
-                } else {
-                    let result = TerminateProcess(h, 1);
-                    let _ = CloseHandle(h);
-                    result.is_ok()
-                }
-            }
-            Err(_) => false,
-        }
-    };
+const ADMIN_STATUS_EVENT: &str = "admin-status";
 
-    if api_result {
-        return KillResult {
-            success: true,
-            message: format!("Port {} freed (killed {})", port, process_name),
-            port,
+// Push whether we're elevated instead of leaving the UI to find out from its
+// first port list, and say so in the window title. Sent from setup and
+// again on every page load, since setup runs before the page can listen. A
+// relaunch from restart_as_admin goes through both again.
+fn announce_admin_status(app: &AppHandle, is_admin: bool) {
+    if let Some(window) = app.get_webview_window("main") {
+        let title = if is_admin {
+            "PortKiller (Administrator)"
+        } else {
+            "PortKiller"
         };
+        let _ = window.set_title(title);
     }
+    let _ = app.emit(ADMIN_STATUS_EVENT, is_admin);
+}
 
-    // Fallback: use taskkill command (works better for services)
-    use std::process::Command;
-    let taskkill_result = Command::new("taskkill")
-        .creation_flags(0x08000000) // CREATE_NO_WINDOW
-        .args(["/F", "/PID", &pid.to_string()])
-        .output();
+const RESTORE_VIEW_ARG: &str = "--restore-view";
+const RESTORE_VIEW_EVENT: &str = "restore-view";
 
-    match taskkill_result {
-        Ok(output) => {
-            if output.status.success() {
-                KillResult {
-                    success: true,
-                    message: format!("Port {} freed (killed {})", port, process_name),
-                    port,
-                }
-            } else {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                if stderr.contains("Access is denied") || stderr.contains("not found") {
-                    KillResult {
-                        success: false,
-                        message: "Access denied. Restart as Administrator.".to_string(),
-                        port,
-                    }
-                } else {
-                    KillResult {
-                        success: false,
-                        message: format!("Failed to kill process: {}", stderr.trim()),
-                        port,
-                    }
-                }
-            }
-        }
-        Err(e) => KillResult {
-            success: false,
-            message: format!("Failed to execute taskkill: {}", e),
-            port,
-        },
-    }
+// What the window was showing when restart_as_admin relaunched it, handed
+// to the elevated instance so the user lands back on the same view.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+#[cfg_attr(feature = "bindings", derive(TS))]
+pub struct ViewState {
+    pub search_query: String,
+    pub protocol_filter: Option<String>,
+    pub selected_port: Option<u16>,
+}
+
+// The handoff file is deleted once read, so a later manual launch with the
+// same arguments doesn't restore a stale view.
+fn take_view_state(path: &Path) -> Option<ViewState> {
+    let content = fs::read_to_string(path).ok();
+    let _ = fs::remove_file(path);
+    serde_json::from_str(&content?).ok()
 }
 
 #[tauri::command]
-fn restart_as_admin(app_handle: AppHandle) -> Result<(), String> {
-    use std::os::windows::process::CommandExt;
-    use std::process::Command;
+fn take_restore_view(data: State<AppData>) -> Option<ViewState> {
+    data.restore_view
+        .lock()
+        .ok()
+        .and_then(|mut view| view.take())
+}
 
+#[tauri::command]
+fn restart_as_admin(app_handle: AppHandle, view: Option<ViewState>) -> Result<(), AppError> {
     let exe = std::env::current_exe().map_err(|e| e.to_string())?;
 
-    // Use ShellExecuteW via PowerShell to properly elevate
-    let result = Command::new("powershell")
-        .creation_flags(0x08000000) // CREATE_NO_WINDOW - hide PowerShell window
-        .args([
-            "-WindowStyle",
-            "Hidden",
-            "-Command",
-            &format!(
-                "Start-Process -FilePath '{}' -Verb RunAs",
-                exe.to_string_lossy().replace("'", "''")
-            ),
-        ])
-        .spawn();
+    // The elevated copy waits for this PID to exit before starting, so the
+    // single-instance check doesn't bounce it back to us.
+    let mut args = vec![WAIT_FOR_PID_ARG.to_string(), std::process::id().to_string()];
+
+    // The view goes through a temp file rather than the command line, which
+    // ShellExecute would need quoted. Best-effort: without it the elevated
+    // instance just opens on the default view.
+    let view_file = view.and_then(|view| {
+        let path =
+            std::env::temp_dir().join(format!("portkiller-view-{}.json", std::process::id()));
+        let json = serde_json::to_string(&view).ok()?;
+        fs::write(&path, json).ok()?;
+        Some(path)
+    });
+    if let Some(path) = &view_file {
+        args.push(RESTORE_VIEW_ARG.to_string());
+        args.push(path.to_string_lossy().into_owned());
+    }
 
-    match result {
-        Ok(_) => {
+    match platform::spawn_elevated(&exe, &args) {
+        Ok(()) => {
             // Exit current instance after spawning elevated one
             std::thread::spawn(move || {
                 std::thread::sleep(std::time::Duration::from_millis(500));
@@ -367,18 +4142,207 @@ fn restart_as_admin(app_handle: AppHandle) -> Result<(), String> {
             });
             Ok(())
         }
-        Err(e) => Err(format!("Failed to restart as admin: {}", e)),
+        Err(e) => {
+            if let Some(path) = view_file {
+                let _ = fs::remove_file(path);
+            }
+            Err(AppError::Other(format!(
+                "Failed to restart as admin: {}",
+                e
+            )))
+        }
     }
 }
 
 #[tauri::command]
-fn set_tray_tooltip(app: AppHandle, text: String) -> Result<(), String> {
+fn set_tray_tooltip(app: AppHandle, text: String) -> Result<(), AppError> {
     if let Some(tray) = app.tray_by_id("main") {
         tray.set_tooltip(Some(text)).map_err(|e| e.to_string())?;
     }
     Ok(())
 }
 
+const DEFAULT_HOTKEY: &str = "Alt+P";
+// Pre-settings.json home of the hotkey; read once to migrate it.
+const LEGACY_HOTKEY_FILE: &str = "hotkey.json";
+const SETTINGS_FILE: &str = "settings.json";
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+#[cfg_attr(feature = "bindings", derive(TS))]
+pub struct Settings {
+    #[cfg_attr(feature = "bindings", ts(type = "number"))]
+    pub refresh_interval_ms: u64,
+    // "tcp" or "udp"; None shows both.
+    pub default_protocol_filter: Option<String>,
+    pub show_protected: bool,
+    pub hotkey: String,
+    // Stay in the tray on launch instead of opening the window.
+    pub start_minimized: bool,
+    // Closing the window hides it to the tray instead of quitting.
+    pub minimize_to_tray: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            refresh_interval_ms: DEFAULT_WATCH_INTERVAL_MS,
+            default_protocol_filter: None,
+            show_protected: true,
+            hotkey: DEFAULT_HOTKEY.to_string(),
+            start_minimized: true,
+            minimize_to_tray: true,
+        }
+    }
+}
+
+fn load_settings(app: &AppHandle) -> Settings {
+    let saved = config_file(app, SETTINGS_FILE)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|raw| serde_json::from_str::<Settings>(&raw).ok());
+    saved.unwrap_or_else(|| Settings {
+        hotkey: load_legacy_hotkey(app).unwrap_or_else(|| DEFAULT_HOTKEY.to_string()),
+        ..Default::default()
+    })
+}
+
+fn write_settings(app: &AppHandle, settings: &Settings) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    fs::write(config_file(app, SETTINGS_FILE)?, json).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_settings(data: State<AppData>) -> Result<Settings, AppError> {
+    data.settings
+        .lock()
+        .map(|s| s.clone())
+        .map_err(|_| AppError::Other("settings mutex poisoned".to_string()))
+}
+
+// A changed hotkey is re-registered before anything is written, so an
+// unavailable combination leaves the old settings untouched.
+#[tauri::command]
+fn save_settings(settings: Settings, app: AppHandle, data: State<AppData>) -> Result<(), AppError> {
+    if let Some(protocol) = &settings.default_protocol_filter {
+        normalize_protocol(protocol)?;
+    }
+    let mut settings = settings;
+    settings.hotkey = apply_hotkey(&app, &data, &settings.hotkey)?;
+    write_settings(&app, &settings)?;
+    *data
+        .settings
+        .lock()
+        .map_err(|_| "settings mutex poisoned".to_string())? = settings;
+    Ok(())
+}
+
+#[tauri::command]
+fn set_close_behavior(
+    minimize: bool,
+    app: AppHandle,
+    data: State<AppData>,
+) -> Result<(), AppError> {
+    let mut settings = data
+        .settings
+        .lock()
+        .map_err(|_| "settings mutex poisoned".to_string())?;
+    settings.minimize_to_tray = minimize;
+    write_settings(&app, &settings).map_err(AppError::from)
+}
+
+// Hide instead of closing when minimize_to_tray is on, so the tray icon and
+// hotkey keep working. The first time per session, say where we went.
+fn handle_close_requested(window: &tauri::Window, api: &tauri::CloseRequestApi) {
+    let app = window.app_handle();
+    let data = app.state::<AppData>();
+    let minimize = data
+        .settings
+        .lock()
+        .map(|s| s.minimize_to_tray)
+        .unwrap_or(true);
+    if !minimize {
+        return;
+    }
+    api.prevent_close();
+    let _ = window.hide();
+    if !data.close_notice_shown.swap(true, Ordering::SeqCst) {
+        let _ = app
+            .notification()
+            .builder()
+            .title("PortKiller")
+            .body("PortKiller is still running in the tray")
+            .show();
+    }
+}
+
+fn parse_hotkey(accelerator: &str) -> Result<Shortcut, String> {
+    accelerator
+        .parse::<Shortcut>()
+        .map_err(|e| format!("Invalid hotkey \"{}\": {}", accelerator, e))
+}
+
+fn load_legacy_hotkey(app: &AppHandle) -> Option<String> {
+    config_file(app, LEGACY_HOTKEY_FILE)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|raw| serde_json::from_str::<String>(&raw).ok())
+}
+
+fn show_menu_label(accelerator: &str) -> String {
+    format!("Show ({})", accelerator)
+}
+
+#[tauri::command]
+fn set_global_hotkey(
+    accelerator: String,
+    app: AppHandle,
+    data: State<AppData>,
+) -> Result<(), AppError> {
+    let accelerator = apply_hotkey(&app, &data, &accelerator)?;
+    let mut settings = data
+        .settings
+        .lock()
+        .map_err(|_| "settings mutex poisoned".to_string())?;
+    settings.hotkey = accelerator;
+    write_settings(&app, &settings).map_err(AppError::from)
+}
+
+// Swap the registered show/hide hotkey and relabel the tray entry. Returns
+// the normalized accelerator.
+fn apply_hotkey(app: &AppHandle, data: &AppData, accelerator: &str) -> Result<String, String> {
+    let accelerator = accelerator.trim().to_string();
+    let shortcut = parse_hotkey(&accelerator)?;
+
+    {
+        let mut binding = data
+            .hotkey
+            .lock()
+            .map_err(|_| "hotkey mutex poisoned".to_string())?;
+        if binding.shortcut != shortcut {
+            let manager = app.global_shortcut();
+            let _ = manager.unregister(binding.shortcut);
+            if let Err(e) = manager.register(shortcut) {
+                // Put the old binding back so the user isn't left without one.
+                let _ = manager.register(binding.shortcut);
+                return Err(format!(
+                    "Hotkey {} is unavailable, it may already be used by another app ({})",
+                    accelerator, e
+                ));
+            }
+        }
+        *binding = HotkeyBinding {
+            shortcut,
+            accelerator: accelerator.clone(),
+        };
+    }
+
+    if let Some(items) = app.try_state::<TrayMenuItems>() {
+        let _ = items.show.set_text(show_menu_label(&accelerator));
+    }
+    Ok(accelerator)
+}
+
 fn show_window(window: &WebviewWindow) {
     // Don't re-center on every show — the frontend persists the user's last
     // position and we want to honor it. center=true in tauri.conf.json still
@@ -407,60 +4371,232 @@ fn handle_tray_toggle(app: &AppHandle) {
     }
 }
 
-// Acquire a single-instance Windows mutex. Returns true if we're the first
-// instance, false if another PortKiller is already running.
-fn acquire_single_instance_lock() -> bool {
-    let name: Vec<u16> = "Global\\PortKiller_SingleInstance_v1\0"
-        .encode_utf16()
-        .collect();
-    unsafe {
-        let handle = CreateMutexW(None, true, PCWSTR(name.as_ptr()));
-        if handle.is_err() {
-            return true; // best-effort: don't block startup if mutex fails
+// Passed by the login entry so PortKiller starts in the tray regardless of
+// the start_minimized setting.
+const MINIMIZED_ARG: &str = "--minimized";
+
+#[tauri::command]
+fn set_autostart(enabled: bool) -> Result<(), AppError> {
+    let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+    platform::set_autostart(&exe, MINIMIZED_ARG, enabled).map_err(AppError::from)
+}
+
+#[tauri::command]
+fn get_autostart() -> bool {
+    platform::is_autostart_enabled()
+}
+
+const WAIT_FOR_PID_ARG: &str = "--wait-for-pid";
+// Upper bound on how long a restarted instance waits for its predecessor.
+const RESTART_WAIT_MS: u64 = 5000;
+
+// Value of `--flag value` or `--flag=value` on the command line.
+fn cli_arg(flag: &str) -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == flag {
+            return args.next();
+        }
+        if let Some(value) = arg.strip_prefix(flag).and_then(|v| v.strip_prefix('=')) {
+            return Some(value.to_string());
         }
-        GetLastError() != ERROR_ALREADY_EXISTS
     }
+    None
 }
 
 fn main() {
-    if !acquire_single_instance_lock() {
-        // Another instance is already running. Bail out — Alt+P / tray click
-        // on the original instance is the way to bring it forward.
+    // Frontend type generation; see bindings.rs.
+    #[cfg(feature = "bindings")]
+    if let Some(dir) = cli_arg(bindings::EMIT_BINDINGS_ARG) {
+        if let Err(e) = bindings::emit(Path::new(&dir)) {
+            eprintln!("Failed to emit bindings: {}", e);
+            std::process::exit(1);
+        }
         return;
     }
 
-    let is_admin = is_running_as_admin();
+    if let Some(pid) = cli_arg(WAIT_FOR_PID_ARG).and_then(|p| p.parse().ok()) {
+        platform::wait_for_exit(pid, RESTART_WAIT_MS);
+    }
+
+    let api_port = api::port_from_args();
+    let is_admin = platform::is_running_as_admin();
     let app_data = AppData {
         system: Mutex::new(System::new_with_specifics(
             RefreshKind::new().with_processes(ProcessRefreshKind::everything()),
         )),
         is_admin,
+        protected: Mutex::new(PROTECTED_PROCESSES.iter().map(|p| p.to_string()).collect()),
+        ignored: Mutex::new(HashSet::new()),
+        favorites: Mutex::new(HashSet::new()),
+        watch_generation: AtomicU64::new(0),
+        watch_active: AtomicBool::new(false),
+        watched_ports: Mutex::new(HashMap::new()),
+        guarded_ports: Mutex::new(HashMap::new()),
+        watchlist: Mutex::new(Vec::new()),
+        reserved_ports: Mutex::new(HashMap::new()),
+        dns_cache: Mutex::new(HashMap::new()),
+        suspended: Mutex::new(HashSet::new()),
+        kill_history: Mutex::new(Vec::new()),
+        history_path: OnceLock::new(),
+        signatures: Mutex::new(HashMap::new()),
+        geoip: Mutex::new(None),
+        settings: Mutex::new(Settings::default()),
+        close_notice_shown: AtomicBool::new(false),
+        hotkey: Mutex::new(HotkeyBinding {
+            shortcut: Shortcut::new(Some(Modifiers::ALT), Code::KeyP),
+            accelerator: DEFAULT_HOTKEY.to_string(),
+        }),
+        logs: Mutex::new(VecDeque::new()),
+        restore_view: Mutex::new(None),
     };
+    if is_admin {
+        if let Err(e) = platform::enable_debug_privilege() {
+            // Not fatal — kills of most processes still work without it.
+            app_data.log("warn", format!("Failed to enable SeDebugPrivilege: {}", e));
+        }
+    }
 
-    let alt_p = Shortcut::new(Some(Modifiers::ALT), Code::KeyP);
-    let alt_p_for_handler = alt_p;
-    let alt_p_for_setup = alt_p;
+    // Editor integrations drive PortKiller over stdin/stdout, with no window.
+    if rpc::requested() {
+        rpc::run(app_data);
+        return;
+    }
 
     tauri::Builder::default()
         .manage(app_data)
+        // Must be registered first: a second launch hands its arguments to
+        // this instance, which surfaces the window, and then exits.
+        .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+            handle_tray_show(app);
+        }))
         .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(
             tauri_plugin_global_shortcut::Builder::new()
                 .with_handler(move |app, shortcut, event| {
-                    if shortcut == &alt_p_for_handler && event.state == ShortcutState::Pressed {
+                    let is_ours = app
+                        .state::<AppData>()
+                        .hotkey
+                        .lock()
+                        .map(|b| &b.shortcut == shortcut)
+                        .unwrap_or(false);
+                    if is_ours && event.state == ShortcutState::Pressed {
                         handle_tray_toggle(app);
                     }
                 })
                 .build(),
         )
+        .on_window_event(|window, event| {
+            if let WindowEvent::CloseRequested { api, .. } = event {
+                handle_close_requested(window, api);
+            }
+        })
+        .on_page_load(move |webview, payload| {
+            if payload.event() == PageLoadEvent::Finished {
+                let _ = webview.emit(ADMIN_STATUS_EVENT, is_admin);
+            }
+        })
         .setup(move |app| {
-            // Register Alt+P globally
-            app.global_shortcut().register(alt_p_for_setup)?;
+            // Merge in the user's persisted protected-process additions.
+            let custom_protected = config_file(app.handle(), PROTECTED_LIST_FILE)
+                .map(|path| load_name_list(&path))
+                .unwrap_or_default();
+            if let Ok(mut set) = app.state::<AppData>().protected.lock() {
+                set.extend(custom_protected);
+            }
+            let ignored = config_file(app.handle(), IGNORE_LIST_FILE)
+                .map(|path| load_name_list(&path))
+                .unwrap_or_default();
+            if let Ok(mut set) = app.state::<AppData>().ignored.lock() {
+                set.extend(ignored);
+            }
+            let favorites = config_file(app.handle(), FAVORITES_FILE)
+                .map(|path| load_name_list(&path))
+                .unwrap_or_default();
+            if let Ok(mut set) = app.state::<AppData>().favorites.lock() {
+                set.extend(favorites);
+            }
+
+            // Set by restart_as_admin. The page may not be listening yet,
+            // so it's also kept for take_restore_view.
+            if let Some(view) =
+                cli_arg(RESTORE_VIEW_ARG).and_then(|p| take_view_state(Path::new(&p)))
+            {
+                let _ = app.emit(RESTORE_VIEW_EVENT, view.clone());
+                if let Ok(mut restore) = app.state::<AppData>().restore_view.lock() {
+                    *restore = Some(view);
+                }
+            }
+
+            if let Ok(mut geoip) = app.state::<AppData>().geoip.lock() {
+                *geoip = load_geoip(app.handle());
+            }
+
+            // Restore the kill audit log from the app data dir.
+            if let Ok(dir) = app.path().app_data_dir() {
+                let _ = fs::create_dir_all(&dir);
+                let path = dir.join(KILL_HISTORY_FILE);
+                let data = app.state::<AppData>();
+                if let Ok(mut history) = data.kill_history.lock() {
+                    *history = load_kill_history(&path);
+                }
+                let _ = data.history_path.set(path);
+            }
+
+            let settings = load_settings(app.handle());
+            let start_minimized =
+                settings.start_minimized || std::env::args().any(|a| a == MINIMIZED_ARG);
+            let refresh_interval_ms = clamp_watch_interval(settings.refresh_interval_ms);
+
+            // Register the saved hotkey globally, falling back to Alt+P if
+            // the saved value no longer parses.
+            let (shortcut, accelerator) = parse_hotkey(&settings.hotkey)
+                .ok()
+                .map(|s| (s, settings.hotkey.clone()))
+                .unwrap_or_else(|| {
+                    (
+                        Shortcut::new(Some(Modifiers::ALT), Code::KeyP),
+                        DEFAULT_HOTKEY.to_string(),
+                    )
+                });
+            // Don't take the whole app down if another program already owns
+            // the combination; the tray icon still works.
+            if let Err(e) = app.global_shortcut().register(shortcut) {
+                app.state::<AppData>().log(
+                    "warn",
+                    format!("Failed to register global hotkey {}: {}", accelerator, e),
+                );
+            }
+            if let Ok(mut binding) = app.state::<AppData>().hotkey.lock() {
+                *binding = HotkeyBinding {
+                    shortcut,
+                    accelerator: accelerator.clone(),
+                };
+            }
+            if let Ok(mut current) = app.state::<AppData>().settings.lock() {
+                *current = Settings {
+                    hotkey: accelerator.clone(),
+                    ..settings
+                };
+            }
 
             // Build tray menu
-            let show_item = MenuItem::with_id(app, "show", "Show (Alt+P)", true, None::<&str>)?;
-            let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-            let menu = Menu::with_items(app, &[&show_item, &quit_item])?;
+            let show_item = MenuItem::with_id(
+                app,
+                "show",
+                show_menu_label(&accelerator),
+                true,
+                None::<&str>,
+            )?;
+            // Dev-port entries are added by the background refresh.
+            let menu = build_tray_menu(app.handle(), &show_item, &[])?;
+            app.manage(TrayMenuItems {
+                show: show_item,
+                dev_ports: Mutex::new(Vec::new()),
+                alert_ports: Mutex::new(Vec::new()),
+            });
 
             // Tray icon — reuse the default window icon embedded by tauri-build.
             let icon = app
@@ -475,7 +4611,14 @@ fn main() {
                 .on_menu_event(|app, event| match event.id().as_ref() {
                     "show" => handle_tray_show(app),
                     "quit" => app.exit(0),
-                    _ => {}
+                    id => {
+                        if let Some(port) = id
+                            .strip_prefix(TRAY_FREE_PREFIX)
+                            .and_then(|port| port.parse().ok())
+                        {
+                            free_port_from_tray(app, port);
+                        }
+                    }
                 })
                 .on_tray_icon_event(|tray, event| {
                     if let TrayIconEvent::Click {
@@ -489,16 +4632,127 @@ fn main() {
                 })
                 .build(app)?;
 
+            if let Some(port) = api_port {
+                if let Err(e) = api::start(app.handle().clone(), port) {
+                    app.state::<AppData>().log(
+                        "error",
+                        format!("Failed to start the HTTP API on port {}: {}", port, e),
+                    );
+                }
+            }
+
+            announce_admin_status(app.handle(), is_admin);
+
+            // Keeps the tray's dev-port entries current even while the
+            // window is hidden.
+            spawn_port_watch(app.handle().clone(), refresh_interval_ms);
+
+            if !start_minimized {
+                handle_tray_show(app.handle());
+            }
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             get_listening_ports,
+            get_all_connections,
+            get_ports_grouped,
+            get_ports_for_pid,
+            get_socket_queues,
+            search_ports,
+            get_connection_stats,
+            get_system_stats,
+            get_reserved_port_ranges,
+            find_port_in_service_configs,
+            is_port_reserved,
+            diagnose_port,
+            find_socket_handle_holders,
+            check_port,
+            probe_port,
+            reset_connection,
+            start_port_watch,
+            stop_port_watch,
+            watch_port,
+            unwatch_port,
+            add_port_guard,
+            remove_port_guard,
+            set_watchlist,
+            reserve_port,
+            release_port,
+            export_ports,
+            export_ports_markdown,
             get_process_details,
+            get_process_ancestry,
+            get_process_environment,
+            get_process_windows,
             open_task_manager,
+            open_process_location,
+            get_kill_command,
+            copy_to_clipboard,
             kill_process,
+            kill_process_with_tree,
+            kill_by_port,
+            resolve_port_conflict,
+            free_port_and_wait,
+            free_dev_ports,
+            kill_by_name,
+            kill_process_tree,
+            kill_processes,
+            restart_process,
+            kill_process_graceful,
+            suspend_process,
+            resume_process,
+            set_process_priority,
+            set_geoip_database,
+            get_kill_history,
+            clear_kill_history,
+            get_logs,
+            clear_logs,
+            get_protected_list,
+            add_protected,
+            remove_protected,
+            get_ignore_list,
+            add_to_ignore,
+            remove_from_ignore,
+            get_favorites,
+            add_favorite,
+            remove_favorite,
+            check_admin,
             restart_as_admin,
-            set_tray_tooltip
+            take_restore_view,
+            set_tray_tooltip,
+            set_global_hotkey,
+            set_close_behavior,
+            get_settings,
+            save_settings,
+            set_autostart,
+            get_autostart
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Full refreshes a watcher polling every `interval_ms` runs in `window_ms`.
+    fn full_refreshes(interval_ms: u64, window_ms: u64) -> u64 {
+        let cycles = window_ms / clamp_watch_interval(interval_ms);
+        (0..cycles).filter(|&c| is_full_refresh_cycle(c)).count() as u64
+    }
+
+    #[test]
+    fn one_second_watch_stays_within_refresh_budget() {
+        // 10 cycles in 10s, and only the first refreshes every process.
+        assert_eq!(full_refreshes(1000, 10_000), 1);
+    }
+
+    #[test]
+    fn fast_polls_are_clamped() {
+        assert_eq!(clamp_watch_interval(10), MIN_WATCH_INTERVAL_MS);
+        assert_eq!(clamp_watch_interval(1000), 1000);
+        // A 10ms request polls at 500ms: 20 cycles, 2 of them full.
+        assert_eq!(full_refreshes(10, 10_000), 2);
+    }
+}
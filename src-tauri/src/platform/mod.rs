@@ -0,0 +1,84 @@
+// OS-specific process control. Socket enumeration (netstat2) and process
+// metadata (sysinfo) are already portable; everything that talks to the OS
+// directly to terminate, signal or elevate lives behind this module.
+
+use crate::error::AppError;
+
+// Scheduling priority, named after the Windows priority classes. Unix maps
+// each level onto a nice value.
+#[derive(Clone, Copy, Debug)]
+pub enum Priority {
+    Idle,
+    BelowNormal,
+    Normal,
+    AboveNormal,
+    High,
+}
+
+impl Priority {
+    pub fn parse(value: &str) -> Option<Priority> {
+        match value.to_lowercase().as_str() {
+            "idle" => Some(Priority::Idle),
+            "below_normal" => Some(Priority::BelowNormal),
+            "normal" => Some(Priority::Normal),
+            "above_normal" => Some(Priority::AboveNormal),
+            "high" => Some(Priority::High),
+            _ => None,
+        }
+    }
+}
+
+// Code-signing status of an executable. `signed` means it carries an
+// embedded signature; `verified` that the signature checks out against a
+// trusted root.
+#[derive(serde::Serialize, Clone, Debug)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+pub struct SignatureInfo {
+    pub signed: bool,
+    pub publisher: Option<String>,
+    pub verified: bool,
+}
+
+// A failed terminate. `os_code` is the raw OS error behind it, when there
+// was one. `transient` marks failures worth retrying: the target was still
+// starting up or already exiting, or briefly in use. A denial that needs
+// elevation (or never goes away, like a protected anti-malware process)
+// isn't transient.
+pub struct TerminateError {
+    pub error: AppError,
+    pub os_code: Option<u32>,
+    pub transient: bool,
+}
+
+impl From<AppError> for TerminateError {
+    fn from(error: AppError) -> Self {
+        TerminateError {
+            error,
+            os_code: None,
+            transient: false,
+        }
+    }
+}
+
+// (local, remote) endpoint of a TCP socket -> (receive, send) queue bytes.
+// Listeners have an unspecified remote (0.0.0.0:0 / [::]:0).
+pub type TcpQueues =
+    std::collections::HashMap<(std::net::SocketAddr, std::net::SocketAddr), (u32, u32)>;
+
+// "KEY=value" from an environment block. The split is on the first `=`
+// after the start, so Windows' hidden per-drive entries ("=C:=C:\src")
+// keep their leading `=`.
+fn env_pair(entry: &str) -> Option<(String, String)> {
+    let split = entry.get(1..)?.find('=')? + 1;
+    Some((entry[..split].to_string(), entry[split + 1..].to_string()))
+}
+
+#[cfg(windows)]
+mod windows;
+#[cfg(windows)]
+pub use self::windows::*;
+
+#[cfg(unix)]
+mod unix;
+#[cfg(unix)]
+pub use self::unix::*;
@@ -0,0 +1,461 @@
+use super::TerminateError;
+use crate::error::AppError;
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, SocketAddr};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+// How long SIGTERM gets before we escalate to SIGKILL.
+const TERM_GRACE: Duration = Duration::from_millis(1500);
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+pub fn is_running_as_admin() -> bool {
+    unsafe { libc::geteuid() == 0 }
+}
+
+// Root can already signal any process; nothing to enable.
+pub fn enable_debug_privilege() -> Result<(), String> {
+    Ok(())
+}
+
+// kill(2) reads 0 as our own process group, -1 as every process we can
+// signal and other negatives as a process group, so only PIDs that map to
+// a positive pid_t are ever passed through.
+fn to_pid_t(pid: u32) -> Result<libc::pid_t, AppError> {
+    match libc::pid_t::try_from(pid) {
+        Ok(pid) if pid > 0 => Ok(pid),
+        _ => Err(AppError::InvalidInput(format!("Invalid PID: {}", pid))),
+    }
+}
+
+fn signal(pid: u32, sig: libc::c_int) -> Result<(), AppError> {
+    if unsafe { libc::kill(to_pid_t(pid)?, sig) } == 0 {
+        return Ok(());
+    }
+    let err = std::io::Error::last_os_error();
+    match err.raw_os_error() {
+        Some(libc::EPERM) => Err(AppError::AccessDenied(
+            "Permission denied. Restart as root.".to_string(),
+        )),
+        Some(libc::ESRCH) => Err(AppError::NotFound(format!("Process {} not found", pid))),
+        _ => Err(AppError::Other(format!("Failed to kill process: {}", err))),
+    }
+}
+
+fn is_alive(pid: u32) -> bool {
+    let Ok(pid) = to_pid_t(pid) else {
+        return false;
+    };
+    if unsafe { libc::kill(pid, 0) } == 0 {
+        return !is_zombie(pid);
+    }
+    // EPERM means it exists but belongs to someone else.
+    std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+// A zombie still answers kill(pid, 0) but has already exited; it's only
+// waiting for its parent to reap it. The state follows the parenthesised
+// command name in /proc/<pid>/stat, which may itself contain ')'.
+fn is_zombie(pid: libc::pid_t) -> bool {
+    std::fs::read_to_string(format!("/proc/{}/stat", pid))
+        .ok()
+        .and_then(|stat| {
+            let (_, rest) = stat.rsplit_once(')')?;
+            rest.trim_start().chars().next()
+        })
+        == Some('Z')
+}
+
+// SIGTERM first so the process can release its sockets cleanly, SIGKILL if
+// it's still around after TERM_GRACE. A signalled process's exit status is
+// set by the signal, so `exit_code` has no Unix equivalent. There is no
+// shell fallback either; the method is always "api", and `tree` (taskkill's
+// /T) is ignored. kill_process_tree walks the children instead.
+pub fn terminate(
+    pid: u32,
+    _exit_code: u32,
+    _allow_fallback: bool,
+    _tree: bool,
+) -> Result<&'static str, TerminateError> {
+    signal(pid, libc::SIGTERM)?;
+    if wait_for_exit(pid, TERM_GRACE.as_millis() as u64) {
+        return Ok("api");
+    }
+    signal(pid, libc::SIGKILL)?;
+    if wait_for_exit(pid, TERM_GRACE.as_millis() as u64) {
+        Ok("api")
+    } else {
+        Err(AppError::Other(format!("Process {} did not exit after SIGKILL", pid)).into())
+    }
+}
+
+// SIGTERM is the Unix equivalent of asking a process to close. It only
+// reaches `pid`, so there are never other recipients to report.
+pub fn request_close(pid: u32) -> Option<Vec<u32>> {
+    signal(pid, libc::SIGTERM).ok().map(|_| Vec::new())
+}
+
+// Open file descriptors, the closest analogue of a Windows handle count.
+// Only readable for our own processes unless running as root.
+pub fn handle_count(pid: u32) -> Option<u32> {
+    std::fs::read_dir(format!("/proc/{}/fd", pid))
+        .ok()
+        .map(|entries| entries.count() as u32)
+}
+
+// Terminal and daemon processes have no window titles to inspect.
+pub fn has_unsaved_window(_pid: u32) -> bool {
+    false
+}
+
+// Mapping windows to PIDs differs between X11 and Wayland compositors (and
+// Wayland doesn't allow it at all), so none are reported.
+pub fn window_titles(_pid: u32) -> Vec<String> {
+    Vec::new()
+}
+
+pub fn is_system_path(path: &Path) -> bool {
+    ["/sbin/", "/usr/sbin/", "/lib/systemd/", "/usr/lib/systemd/"]
+        .iter()
+        .any(|dir| path.starts_with(dir))
+}
+
+pub fn image_path(pid: u32) -> Option<std::path::PathBuf> {
+    std::fs::read_link(format!("/proc/{}/exe", pid)).ok()
+}
+
+pub fn is_os_binary(path: &Path) -> bool {
+    is_system_path(path)
+}
+
+pub fn process_environment(pid: u32) -> Result<Vec<(String, String)>, AppError> {
+    let raw = std::fs::read(format!("/proc/{}/environ", pid)).map_err(|e| match e.kind() {
+        std::io::ErrorKind::PermissionDenied => AppError::AccessDenied(format!(
+            "Access denied reading the environment of PID {}. Restart as root.",
+            pid
+        )),
+        std::io::ErrorKind::NotFound => AppError::NotFound(format!("Process {} not found", pid)),
+        _ => AppError::from(e),
+    })?;
+    Ok(raw
+        .split(|&b| b == 0)
+        .filter_map(|entry| super::env_pair(&String::from_utf8_lossy(entry)))
+        .collect())
+}
+
+// Inodes of the sockets bound to `port`, from the kernel's socket tables.
+// TIME_WAIT entries have inode 0 and no owner, so they're skipped.
+fn socket_inodes(port: u16) -> HashSet<u64> {
+    let mut inodes = HashSet::new();
+    for table in ["tcp", "tcp6", "udp", "udp6"] {
+        let Ok(raw) = std::fs::read_to_string(format!("/proc/net/{}", table)) else {
+            continue;
+        };
+        for line in raw.lines().skip(1) {
+            // local_address is "HEXADDR:HEXPORT"; the inode is column ten.
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let bound = fields
+                .get(1)
+                .and_then(|local| local.rsplit_once(':'))
+                .and_then(|(_, p)| u16::from_str_radix(p, 16).ok());
+            if bound != Some(port) {
+                continue;
+            }
+            if let Some(inode) = fields.get(9).and_then(|i| i.parse().ok()) {
+                if inode != 0 {
+                    inodes.insert(inode);
+                }
+            }
+        }
+    }
+    inodes
+}
+
+// Every process with an fd open on one of the port's sockets. Other users'
+// fd tables are only readable as root.
+pub fn socket_handle_holders(port: u16) -> Result<Vec<u32>, AppError> {
+    let inodes = socket_inodes(port);
+    if inodes.is_empty() {
+        return Ok(Vec::new());
+    }
+    let own = std::process::id();
+    let mut holders = Vec::new();
+    for entry in std::fs::read_dir("/proc")?.flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|n| n.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        if pid == own {
+            continue;
+        }
+        let Ok(fds) = std::fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+        // Socket fds link to "socket:[<inode>]".
+        let holds = fds.flatten().any(|fd| {
+            std::fs::read_link(fd.path())
+                .ok()
+                .and_then(|target| {
+                    target
+                        .to_str()?
+                        .strip_prefix("socket:[")?
+                        .strip_suffix(']')?
+                        .parse::<u64>()
+                        .ok()
+                })
+                .is_some_and(|inode| inodes.contains(&inode))
+        });
+        if holds {
+            holders.push(pid);
+        }
+    }
+    holders.sort_unstable();
+    Ok(holders)
+}
+
+// From the tx_queue:rx_queue column of /proc/net/tcp{,6}. For a listener
+// the receive queue counts connections the kernel has completed but the
+// server hasn't accepted yet, rather than bytes.
+pub fn tcp_queues() -> super::TcpQueues {
+    let mut queues = HashMap::new();
+    for table in ["tcp", "tcp6"] {
+        let Ok(raw) = std::fs::read_to_string(format!("/proc/net/{}", table)) else {
+            continue;
+        };
+        for line in raw.lines().skip(1) {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let (Some(local), Some(remote), Some((tx, rx))) = (
+                fields.get(1).and_then(|a| proc_socket_addr(a)),
+                fields.get(2).and_then(|a| proc_socket_addr(a)),
+                fields.get(4).and_then(|q| q.split_once(':')),
+            ) else {
+                continue;
+            };
+            if let (Ok(tx), Ok(rx)) = (u32::from_str_radix(tx, 16), u32::from_str_radix(rx, 16)) {
+                queues.insert((local, remote), (rx, tx));
+            }
+        }
+    }
+    queues
+}
+
+// "0100007F:1F90" -> 127.0.0.1:8080. The address is 8 (IPv4) or 32 (IPv6)
+// hex digits, each 32-bit word printed in host byte order.
+fn proc_socket_addr(field: &str) -> Option<SocketAddr> {
+    let (ip, port) = field.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    let mut bytes = Vec::with_capacity(16);
+    for start in (0..ip.len()).step_by(8) {
+        let word = u32::from_str_radix(ip.get(start..start + 8)?, 16).ok()?;
+        bytes.extend_from_slice(&word.to_ne_bytes());
+    }
+    let ip = match bytes.len() {
+        4 => IpAddr::from(<[u8; 4]>::try_from(bytes).ok()?),
+        16 => IpAddr::from(<[u8; 16]>::try_from(bytes).ok()?),
+        _ => return None,
+    };
+    Some(SocketAddr::new(ip, port))
+}
+
+// ELF binaries carry no Authenticode-style signature to report.
+pub fn signature(_path: &Path) -> Option<super::SignatureInfo> {
+    None
+}
+
+// Daemons are started (or reparented) by init / systemd.
+pub fn is_service_host(parent_name: &str) -> bool {
+    matches!(parent_name, "systemd" | "init" | "launchd")
+}
+
+// Poll until `pid` exits or `timeout_ms` elapses. Returns true if it exited.
+pub fn wait_for_exit(pid: u32, timeout_ms: u64) -> bool {
+    let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+    while is_alive(pid) {
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    true
+}
+
+pub fn suspend(pid: u32) -> Result<(), AppError> {
+    signal(pid, libc::SIGSTOP)
+}
+
+pub fn resume(pid: u32) -> Result<(), AppError> {
+    signal(pid, libc::SIGCONT)
+}
+
+// Raising priority (negative nice) needs root; lowering it works on our own
+// processes.
+pub fn set_priority(pid: u32, priority: super::Priority) -> Result<(), AppError> {
+    use super::Priority;
+    let nice = match priority {
+        Priority::Idle => 19,
+        Priority::BelowNormal => 10,
+        Priority::Normal => 0,
+        Priority::AboveNormal => -5,
+        Priority::High => -10,
+    };
+    let who = to_pid_t(pid)? as libc::id_t;
+    if unsafe { libc::setpriority(libc::PRIO_PROCESS, who, nice) } == 0 {
+        return Ok(());
+    }
+    let err = std::io::Error::last_os_error();
+    match err.raw_os_error() {
+        Some(libc::EPERM) | Some(libc::EACCES) => Err(AppError::AccessDenied(
+            "Permission denied. Restart as root.".to_string(),
+        )),
+        Some(libc::ESRCH) => Err(AppError::NotFound(format!("Process {} not found", pid))),
+        _ => Err(AppError::Other(format!("Failed to set priority: {}", err))),
+    }
+}
+
+// Linux keeps one reserved list for both protocols, formatted like
+// "8080,9000-9010".
+pub fn reserved_port_ranges(_protocol: &str) -> Result<Vec<(u16, u16)>, String> {
+    let raw = match std::fs::read_to_string("/proc/sys/net/ipv4/ip_local_reserved_ports") {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.to_string()),
+    };
+    Ok(raw
+        .trim()
+        .split(',')
+        .filter_map(|entry| {
+            let (start, end) = entry.split_once('-').unwrap_or((entry, entry));
+            Some((start.trim().parse().ok()?, end.trim().parse().ok()?))
+        })
+        .collect())
+}
+
+pub fn open_task_manager() -> Result<(), String> {
+    Err("Task Manager is only available on Windows".to_string())
+}
+
+// No portable "select this file" verb on Linux; open the containing folder.
+pub fn reveal_in_file_manager(path: &Path) -> Result<(), String> {
+    let dir = path.parent().unwrap_or(path);
+    std::process::Command::new("xdg-open")
+        .arg(dir)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+pub fn spawn_elevated(_exe: &Path, _args: &[String]) -> Result<(), String> {
+    Err("Relaunching elevated is only supported on Windows; start PortKiller with sudo".to_string())
+}
+
+// XDG autostart entry, honoured by every mainstream Linux desktop.
+fn autostart_entry() -> Result<std::path::PathBuf, String> {
+    let config = std::env::var_os("XDG_CONFIG_HOME")
+        .map(std::path::PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| Path::new(&h).join(".config")))
+        .ok_or("Cannot locate the user config directory")?;
+    Ok(config.join("autostart").join("portkiller.desktop"))
+}
+
+pub fn set_autostart(exe: &Path, args: &str, enabled: bool) -> Result<(), String> {
+    let entry = autostart_entry()?;
+    if !enabled {
+        return match std::fs::remove_file(&entry) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+            _ => Ok(()),
+        };
+    }
+    if let Some(dir) = entry.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let desktop = format!(
+        "[Desktop Entry]\nType=Application\nName=PortKiller\nExec=\"{}\" {}\n",
+        exe.display(),
+        args
+    );
+    std::fs::write(&entry, desktop).map_err(|e| e.to_string())
+}
+
+pub fn is_autostart_enabled() -> bool {
+    autostart_entry().is_ok_and(|entry| entry.exists())
+}
+
+const SYSTEMD_UNIT_DIRS: &[&str] = &[
+    "/etc/systemd/system",
+    "/lib/systemd/system",
+    "/usr/lib/systemd/system",
+];
+
+// (unit name, ExecStart lines) for every systemd service unit on disk.
+// Missing unit directories are skipped.
+pub fn service_command_lines() -> Result<Vec<(String, String)>, String> {
+    let mut services = Vec::new();
+    for dir in SYSTEMD_UNIT_DIRS {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "service") {
+                continue;
+            }
+            let Ok(unit) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            let exec: Vec<&str> = unit
+                .lines()
+                .map(str::trim)
+                .filter(|line| line.starts_with("ExecStart"))
+                .collect();
+            if let (Some(name), false) = (path.file_stem(), exec.is_empty()) {
+                services.push((name.to_string_lossy().to_string(), exec.join("\n")));
+            }
+        }
+    }
+    Ok(services)
+}
+
+pub fn reset_tcp_connection(
+    _local: std::net::SocketAddrV4,
+    _remote: std::net::SocketAddrV4,
+) -> Result<(), AppError> {
+    Err(AppError::Other(
+        "Resetting connections is only supported on Windows; use `ss -K` as root".to_string(),
+    ))
+}
+
+// Addresses of every interface, via getifaddrs. Empty on failure.
+pub fn local_addresses() -> Vec<std::net::IpAddr> {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+    let mut addresses = Vec::new();
+    unsafe {
+        let mut ifap: *mut libc::ifaddrs = std::ptr::null_mut();
+        if libc::getifaddrs(&mut ifap) != 0 {
+            return addresses;
+        }
+        let mut ifa = ifap;
+        while !ifa.is_null() {
+            let addr = (*ifa).ifa_addr;
+            if !addr.is_null() {
+                match (*addr).sa_family as libc::c_int {
+                    libc::AF_INET => {
+                        let v4 = &*(addr as *const libc::sockaddr_in);
+                        addresses
+                            .push(IpAddr::V4(Ipv4Addr::from(u32::from_be(v4.sin_addr.s_addr))));
+                    }
+                    libc::AF_INET6 => {
+                        let v6 = &*(addr as *const libc::sockaddr_in6);
+                        addresses.push(IpAddr::V6(Ipv6Addr::from(v6.sin6_addr.s6_addr)));
+                    }
+                    _ => {}
+                }
+            }
+            ifa = (*ifa).ifa_next;
+        }
+        libc::freeifaddrs(ifap);
+    }
+    addresses
+}
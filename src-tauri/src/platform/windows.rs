@@ -0,0 +1,1373 @@
+use super::TerminateError;
+use crate::error::AppError;
+use std::collections::{BTreeSet, HashMap};
+use std::ffi::OsString;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddrV4};
+use std::os::windows::ffi::OsStringExt;
+use std::os::windows::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{mpsc, Mutex};
+use std::time::Duration;
+use windows::core::{s, w, PCWSTR, PWSTR};
+use windows::Win32::Foundation::{
+    CloseHandle, DuplicateHandle, GetLastError, BOOL, DUPLICATE_SAME_ACCESS, ERROR_ACCESS_DENIED,
+    ERROR_BUFFER_OVERFLOW, ERROR_BUSY, ERROR_FILE_NOT_FOUND, ERROR_INVALID_PARAMETER,
+    ERROR_NOT_ALL_ASSIGNED, ERROR_NO_MORE_ITEMS, ERROR_SHARING_VIOLATION, ERROR_SUCCESS, FALSE,
+    FILETIME, HANDLE, HWND, LPARAM, LUID, NTSTATUS, STILL_ACTIVE, TRUE, WAIT_OBJECT_0, WPARAM,
+};
+use windows::Win32::NetworkManagement::IpHelper::{
+    GetAdaptersAddresses, SetTcpEntry, GAA_FLAG_SKIP_ANYCAST, GAA_FLAG_SKIP_DNS_SERVER,
+    GAA_FLAG_SKIP_MULTICAST, IP_ADAPTER_ADDRESSES_LH, MIB_TCPROW_LH, MIB_TCPROW_LH_0,
+    MIB_TCP_STATE_DELETE_TCB,
+};
+use windows::Win32::NetworkManagement::Ndis::IfOperStatusUp;
+use windows::Win32::Networking::WinSock::{
+    getsockname, WSACleanup, WSAStartup, AF_INET, AF_INET6, AF_UNSPEC, SOCKADDR, SOCKADDR_IN,
+    SOCKADDR_IN6, SOCKADDR_STORAGE, SOCKET, WSADATA,
+};
+use windows::Win32::Security::Cryptography::{
+    CertCloseStore, CertFindCertificateInStore, CertFreeCertificateContext, CertGetNameStringW,
+    CryptMsgClose, CryptMsgGetParam, CryptQueryObject, CERT_FIND_SUBJECT_CERT, CERT_INFO,
+    CERT_NAME_SIMPLE_DISPLAY_TYPE, CERT_QUERY_CONTENT_FLAG_PKCS7_SIGNED_EMBED,
+    CERT_QUERY_FORMAT_FLAG_BINARY, CERT_QUERY_OBJECT_FILE, CMSG_SIGNER_INFO,
+    CMSG_SIGNER_INFO_PARAM, HCERTSTORE, PKCS_7_ASN_ENCODING, X509_ASN_ENCODING,
+};
+use windows::Win32::Security::WinTrust::{
+    WinVerifyTrust, WINTRUST_ACTION_GENERIC_VERIFY_V2, WINTRUST_DATA, WINTRUST_DATA_0,
+    WINTRUST_FILE_INFO, WTD_CHOICE_FILE, WTD_REVOKE_NONE, WTD_STATEACTION_CLOSE,
+    WTD_STATEACTION_VERIFY, WTD_UI_NONE,
+};
+use windows::Win32::Security::{
+    AdjustTokenPrivileges, GetTokenInformation, LookupPrivilegeValueW, TokenElevation,
+    LUID_AND_ATTRIBUTES, SE_DEBUG_NAME, SE_PRIVILEGE_ENABLED, TOKEN_ADJUST_PRIVILEGES,
+    TOKEN_ELEVATION, TOKEN_PRIVILEGES, TOKEN_QUERY,
+};
+use windows::Win32::System::Console::{
+    AttachConsole, FreeConsole, GenerateConsoleCtrlEvent, GetConsoleProcessList,
+    SetConsoleCtrlHandler, ATTACH_PARENT_PROCESS, CTRL_BREAK_EVENT,
+};
+use windows::Win32::System::Diagnostics::Debug::ReadProcessMemory;
+use windows::Win32::System::LibraryLoader::{GetModuleHandleW, GetProcAddress};
+use windows::Win32::System::Registry::{
+    RegCloseKey, RegDeleteValueW, RegEnumKeyExW, RegGetValueW, RegOpenKeyExW, RegQueryValueExW,
+    RegSetValueExW, HKEY, HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE, KEY_QUERY_VALUE, KEY_READ,
+    KEY_SET_VALUE, REG_SAM_FLAGS, REG_SZ, RRF_RT_REG_SZ,
+};
+use windows::Win32::System::Threading::{
+    GetCurrentProcess, GetCurrentProcessId, GetExitCodeProcess, GetProcessHandleCount,
+    GetProcessTimes, OpenProcess, OpenProcessToken, QueryFullProcessImageNameW, SetPriorityClass,
+    TerminateProcess, WaitForSingleObject, ABOVE_NORMAL_PRIORITY_CLASS,
+    BELOW_NORMAL_PRIORITY_CLASS, HIGH_PRIORITY_CLASS, IDLE_PRIORITY_CLASS, NORMAL_PRIORITY_CLASS,
+    PROCESS_DUP_HANDLE, PROCESS_NAME_WIN32, PROCESS_QUERY_INFORMATION,
+    PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_SET_INFORMATION, PROCESS_SUSPEND_RESUME,
+    PROCESS_SYNCHRONIZE, PROCESS_TERMINATE, PROCESS_VM_READ,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    EnumWindows, GetWindowTextLengthW, GetWindowTextW, GetWindowThreadProcessId, IsWindowVisible,
+    PostMessageW, WM_CLOSE,
+};
+
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+// How long a terminated process gets to actually disappear before we report
+// failure. TerminateProcess is asynchronous; a thread stuck in a kernel call
+// can keep the process (and its sockets) alive for a moment.
+const EXIT_CONFIRM_MS: u64 = 2000;
+// A process younger than this that denies PROCESS_TERMINATE is assumed to
+// still be starting up, and the denial worth retrying.
+const STARTUP_WINDOW_MS: u64 = 1000;
+
+// Detect process elevation via the Win32 token API. Avoids the ~100 ms cost of
+// shelling `net session` on the critical startup path.
+pub fn is_running_as_admin() -> bool {
+    unsafe {
+        let mut token: HANDLE = HANDLE::default();
+        if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token).is_err() {
+            return false;
+        }
+
+        let mut elevation = TOKEN_ELEVATION::default();
+        let size = std::mem::size_of::<TOKEN_ELEVATION>() as u32;
+        let mut returned = 0u32;
+        let result = GetTokenInformation(
+            token,
+            TokenElevation,
+            Some(&mut elevation as *mut _ as *mut _),
+            size,
+            &mut returned,
+        );
+        let _ = CloseHandle(token);
+
+        result.is_ok() && elevation.TokenIsElevated != 0
+    }
+}
+
+// Enable SeDebugPrivilege on our own token. Being elevated isn't enough to
+// open some system/integrity-protected processes for PROCESS_TERMINATE; the
+// privilege has to be switched on explicitly. Only meaningful when elevated.
+pub fn enable_debug_privilege() -> Result<(), String> {
+    unsafe {
+        let mut token = HANDLE::default();
+        OpenProcessToken(
+            GetCurrentProcess(),
+            TOKEN_ADJUST_PRIVILEGES | TOKEN_QUERY,
+            &mut token,
+        )
+        .map_err(|e| e.to_string())?;
+
+        let mut luid = LUID::default();
+        if let Err(e) = LookupPrivilegeValueW(PCWSTR::null(), SE_DEBUG_NAME, &mut luid) {
+            let _ = CloseHandle(token);
+            return Err(e.to_string());
+        }
+
+        let privileges = TOKEN_PRIVILEGES {
+            PrivilegeCount: 1,
+            Privileges: [LUID_AND_ATTRIBUTES {
+                Luid: luid,
+                Attributes: SE_PRIVILEGE_ENABLED,
+            }],
+        };
+        let result =
+            AdjustTokenPrivileges(token, false, Some(&privileges as *const _), 0, None, None);
+        // AdjustTokenPrivileges "succeeds" even when it assigned nothing, so
+        // the last error has to be checked as well.
+        let not_assigned = GetLastError() == ERROR_NOT_ALL_ASSIGNED;
+        let _ = CloseHandle(token);
+
+        result.map_err(|e| e.to_string())?;
+        if not_assigned {
+            return Err("SeDebugPrivilege is not held by this token".to_string());
+        }
+        Ok(())
+    }
+}
+
+// Force-terminate `pid` with `exit_code`, returning only once it's gone.
+// Ok carries the method that worked: "api" (TerminateProcess) or "taskkill".
+// Errors carry the message shown to the user. `tree` goes straight to
+// `taskkill /T`, which takes the child tree down too; TerminateProcess
+// would only stop the root, so there's no API attempt to fall back from.
+// That makes `tree` a shell-out by definition, refused when the caller has
+// ruled those out with `allow_fallback: false`.
+pub fn terminate(
+    pid: u32,
+    exit_code: u32,
+    allow_fallback: bool,
+    tree: bool,
+) -> Result<&'static str, TerminateError> {
+    if tree && !allow_fallback {
+        return Err(AppError::InvalidInput(
+            "Killing a process tree needs taskkill; allow_fallback must be set".to_string(),
+        )
+        .into());
+    }
+    let method = if tree {
+        taskkill(pid, true)?
+    } else {
+        force_terminate(pid, exit_code, allow_fallback)?
+    };
+    if wait_for_exit(pid, EXIT_CONFIRM_MS) {
+        Ok(method)
+    } else {
+        Err(AppError::Other(format!(
+            "Process {} is still running {} ms after termination",
+            pid, EXIT_CONFIRM_MS
+        ))
+        .into())
+    }
+}
+
+fn force_terminate(
+    pid: u32,
+    exit_code: u32,
+    allow_fallback: bool,
+) -> Result<&'static str, TerminateError> {
+    // First try Windows API
+    let api_result = unsafe {
+        match OpenProcess(PROCESS_TERMINATE, false, pid) {
+            Ok(h) if h.is_invalid() => {
+                Err(AppError::Other(format!("Failed to open process {}", pid)).into())
+            }
+            Ok(h) => {
+                let result = TerminateProcess(h, exit_code);
+                let _ = CloseHandle(h);
+                result.map_err(|e| terminate_error(pid, e))
+            }
+            Err(e) => Err(terminate_error(pid, e)),
+        }
+    };
+
+    match api_result {
+        Ok(()) => return Ok("api"),
+        // Spawning taskkill is slower and shows up in process-creation
+        // audits; some users would rather take the API failure as final.
+        // A transient failure goes back to the caller to retry the API
+        // rather than being handed to taskkill, which would fail the same.
+        Err(e) if !allow_fallback || e.transient => return Err(e),
+        Err(_) => {}
+    }
+
+    // Fallback: use taskkill command (works better for services)
+    Ok(taskkill(pid, false)?)
+}
+
+// The Win32 code inside an HRESULT_FROM_WIN32 value.
+fn win32_code(error: &windows::core::Error) -> Option<u32> {
+    let hresult = error.code().0 as u32;
+    (hresult & 0xFFFF_0000 == 0x8007_0000).then_some(hresult & 0xFFFF)
+}
+
+fn terminate_error(pid: u32, error: windows::core::Error) -> TerminateError {
+    let os_code = win32_code(&error);
+    let transient = match os_code {
+        Some(code) if code == ERROR_SHARING_VIOLATION.0 || code == ERROR_BUSY.0 => true,
+        // Denied while starting up or exiting is a passing state; denied
+        // otherwise (elevation, a protected process) stays denied.
+        Some(code) if code == ERROR_ACCESS_DENIED.0 => in_transition(pid),
+        _ => false,
+    };
+    TerminateError {
+        error: api_error(pid, error),
+        os_code,
+        transient,
+    }
+}
+
+// Whether `pid` is already exiting or was created within STARTUP_WINDOW_MS.
+// PROCESS_QUERY_LIMITED_INFORMATION is granted even on protected processes,
+// which are long-running, so those come out false.
+fn in_transition(pid: u32) -> bool {
+    unsafe {
+        let Ok(handle) = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) else {
+            return false;
+        };
+        let mut exit_code = 0u32;
+        let exiting = GetExitCodeProcess(handle, &mut exit_code).is_ok()
+            && exit_code != STILL_ACTIVE.0 as u32;
+        let (mut created, mut exited, mut kernel, mut user) = (
+            FILETIME::default(),
+            FILETIME::default(),
+            FILETIME::default(),
+            FILETIME::default(),
+        );
+        let times = GetProcessTimes(handle, &mut created, &mut exited, &mut kernel, &mut user);
+        let _ = CloseHandle(handle);
+        if exiting {
+            return true;
+        }
+        if times.is_err() {
+            return false;
+        }
+        // FILETIME counts 100ns intervals since 1601-01-01.
+        const UNIX_EPOCH_FILETIME: u64 = 116_444_736_000_000_000;
+        let created = (created.dwHighDateTime as u64) << 32 | created.dwLowDateTime as u64;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64 / 100 + UNIX_EPOCH_FILETIME)
+            .unwrap_or(0);
+        now.saturating_sub(created) < STARTUP_WINDOW_MS * 10_000
+    }
+}
+
+fn taskkill(pid: u32, tree: bool) -> Result<&'static str, AppError> {
+    let pid_arg = pid.to_string();
+    let mut args = vec!["/F", "/PID", pid_arg.as_str()];
+    if tree {
+        args.push("/T");
+    }
+    let taskkill_result = Command::new("taskkill")
+        .creation_flags(CREATE_NO_WINDOW)
+        .args(&args)
+        .output();
+
+    match taskkill_result {
+        Ok(output) => {
+            if output.status.success() {
+                Ok("taskkill")
+            } else {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                if stderr.contains("Access is denied") {
+                    Err(AppError::AccessDenied(
+                        "Access denied. Restart as Administrator.".to_string(),
+                    ))
+                } else if stderr.contains("not found") {
+                    Err(AppError::NotFound(format!("Process {} not found", pid)))
+                } else {
+                    Err(AppError::Other(format!(
+                        "Failed to kill process: {}",
+                        stderr.trim()
+                    )))
+                }
+            }
+        }
+        Err(e) => Err(AppError::Other(format!(
+            "Failed to execute taskkill: {}",
+            e
+        ))),
+    }
+}
+
+fn api_error(pid: u32, error: windows::core::Error) -> AppError {
+    if error.code() == ERROR_ACCESS_DENIED.to_hresult() {
+        AppError::AccessDenied("Access denied. Restart as Administrator.".to_string())
+    } else if error.code() == ERROR_INVALID_PARAMETER.to_hresult() {
+        // OpenProcess reports a PID that doesn't exist as an invalid parameter.
+        AppError::NotFound(format!("Process {} not found", pid))
+    } else {
+        AppError::Other(format!("Failed to kill process: {}", error))
+    }
+}
+
+unsafe extern "system" fn collect_process_windows(hwnd: HWND, lparam: LPARAM) -> BOOL {
+    let (target, windows) = &mut *(lparam.0 as *mut (u32, Vec<HWND>));
+    let mut owner = 0u32;
+    GetWindowThreadProcessId(hwnd, Some(&mut owner as *mut u32));
+    if owner == *target {
+        windows.push(hwnd);
+    }
+    TRUE
+}
+
+// Top-level windows owned by `pid`. Console apps own none — their window
+// belongs to conhost — so an empty result means WM_CLOSE can't help.
+fn top_level_windows(pid: u32) -> Vec<HWND> {
+    let mut ctx: (u32, Vec<HWND>) = (pid, Vec::new());
+    unsafe {
+        let _ = EnumWindows(
+            Some(collect_process_windows),
+            LPARAM(&mut ctx as *mut _ as isize),
+        );
+    }
+    ctx.1
+}
+
+// Ask `pid` to close: WM_CLOSE to its top-level windows, or Ctrl+Break for
+// a console app (dev servers shut down cleanly on it, releasing their
+// ports). None when there was nothing to send the request to; otherwise the
+// other processes Ctrl+Break reached too (see send_ctrl_break).
+pub fn request_close(pid: u32) -> Option<Vec<u32>> {
+    let windows = top_level_windows(pid);
+    for hwnd in &windows {
+        unsafe {
+            let _ = PostMessageW(*hwnd, WM_CLOSE, WPARAM(0), LPARAM(0));
+        }
+    }
+    if windows.is_empty() {
+        send_ctrl_break(pid)
+    } else {
+        Some(Vec::new())
+    }
+}
+
+// A process can only be attached to one console at a time, so concurrent
+// graceful kills take turns.
+static CONSOLE_LOCK: Mutex<()> = Mutex::new(());
+// The event reaches us asynchronously, so our handler stays installed this
+// long after sending before it's removed.
+const CTRL_EVENT_DELIVERY: Duration = Duration::from_millis(200);
+
+// Installed while we're attached to someone else's console. We're in the
+// group Ctrl+Break goes to, and the default handler would exit PortKiller;
+// SetConsoleCtrlHandler(None, TRUE) only covers Ctrl+C.
+unsafe extern "system" fn swallow_ctrl_break(ctrl_type: u32) -> BOOL {
+    if ctrl_type == CTRL_BREAK_EVENT {
+        TRUE
+    } else {
+        FALSE
+    }
+}
+
+// Attach to `pid`'s console and raise Ctrl+Break on it. None if `pid` has
+// no console (a GUI or detached process). Group 0 means every process on
+// that console receives it, e.g. sibling servers started by the same
+// `npm run dev` and the shell itself, just like pressing Ctrl+Break in the
+// terminal; those other PIDs are returned so the caller can say so.
+fn send_ctrl_break(pid: u32) -> Option<Vec<u32>> {
+    let Ok(_guard) = CONSOLE_LOCK.lock() else {
+        return None;
+    };
+    unsafe {
+        // Debug builds run with a console of their own; detach from it first
+        // and reattach afterwards.
+        let had_console = FreeConsole().is_ok();
+        let sent = if AttachConsole(pid).is_ok() {
+            // Returns the full count, filling nothing, when the buffer is
+            // too small.
+            let mut attached = vec![0u32; 64];
+            let mut count = GetConsoleProcessList(&mut attached) as usize;
+            if count > attached.len() {
+                attached.resize(count, 0);
+                count = GetConsoleProcessList(&mut attached) as usize;
+            }
+            attached.truncate(count);
+            let own = GetCurrentProcessId();
+            let others: Vec<u32> = attached
+                .into_iter()
+                .filter(|&other| other != pid && other != own)
+                .collect();
+
+            let _ = SetConsoleCtrlHandler(Some(swallow_ctrl_break), TRUE);
+            let sent = GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, 0).is_ok();
+            let _ = FreeConsole();
+            if sent {
+                std::thread::sleep(CTRL_EVENT_DELIVERY);
+            }
+            let _ = SetConsoleCtrlHandler(Some(swallow_ctrl_break), FALSE);
+            sent.then_some(others)
+        } else {
+            None
+        };
+        if had_console {
+            let _ = AttachConsole(ATTACH_PARENT_PROCESS);
+        }
+        sent
+    }
+}
+
+// Number of kernel handles `pid` holds open; a steadily climbing count
+// points at a leak.
+pub fn handle_count(pid: u32) -> Option<u32> {
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut count = 0u32;
+        let result = GetProcessHandleCount(handle, &mut count);
+        let _ = CloseHandle(handle);
+        result.ok().map(|_| count)
+    }
+}
+
+// Titles of `pid`'s visible top-level windows, trimmed. Untitled windows
+// (message-only helpers, splash frames) are skipped.
+pub fn window_titles(pid: u32) -> Vec<String> {
+    top_level_windows(pid)
+        .into_iter()
+        .filter_map(|hwnd| unsafe {
+            if !IsWindowVisible(hwnd).as_bool() {
+                return None;
+            }
+            let mut buf = vec![0u16; GetWindowTextLengthW(hwnd).max(0) as usize + 1];
+            let len = GetWindowTextW(hwnd, &mut buf).max(0) as usize;
+            let title = String::from_utf16_lossy(&buf[..len]).trim().to_string();
+            (!title.is_empty()).then_some(title)
+        })
+        .collect()
+}
+
+// Best-effort "unsaved work" check: most editors mark a dirty document with
+// a leading `*` or `●` in the window title.
+pub fn has_unsaved_window(pid: u32) -> bool {
+    window_titles(pid)
+        .iter()
+        .any(|title| title.starts_with('*') || title.starts_with('●') || title.ends_with('*'))
+}
+
+// Executables under the Windows system directories.
+pub fn is_system_path(path: &Path) -> bool {
+    let root = std::env::var("SystemRoot").unwrap_or_else(|_| "C:\\Windows".to_string());
+    let path = path.to_string_lossy().to_lowercase();
+    ["System32", "SysWOW64"].iter().any(|dir| {
+        let prefix = format!("{}\\{}\\", root, dir).to_lowercase();
+        path.starts_with(&prefix)
+    })
+}
+
+// Full path of the executable behind `pid`, straight from the kernel rather
+// than sysinfo's snapshot. None if the process can't be opened.
+pub fn image_path(pid: u32) -> Option<PathBuf> {
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut buf = [0u16; 1024];
+        let mut len = buf.len() as u32;
+        let result = QueryFullProcessImageNameW(
+            handle,
+            PROCESS_NAME_WIN32,
+            PWSTR(buf.as_mut_ptr()),
+            &mut len,
+        );
+        let _ = CloseHandle(handle);
+        result.ok()?;
+        Some(PathBuf::from(OsString::from_wide(&buf[..len as usize])))
+    }
+}
+
+// Authenticode status of the file at `path`. Only embedded signatures are
+// seen: binaries signed through a system catalog (much of Windows itself)
+// report unsigned, as do files we can't read.
+pub fn signature(path: &Path) -> Option<super::SignatureInfo> {
+    let wide: Vec<u16> = path
+        .to_string_lossy()
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+    let publisher = signer_name(&wide);
+    Some(super::SignatureInfo {
+        signed: publisher.is_some(),
+        verified: verify_trust(&wide),
+        publisher: publisher.filter(|name| !name.is_empty()),
+    })
+}
+
+// WinVerifyTrust against the embedded signature, without UI or revocation
+// checks (those can hit the network).
+fn verify_trust(wide_path: &[u16]) -> bool {
+    unsafe {
+        let mut file = WINTRUST_FILE_INFO {
+            cbStruct: std::mem::size_of::<WINTRUST_FILE_INFO>() as u32,
+            pcwszFilePath: PCWSTR(wide_path.as_ptr()),
+            ..Default::default()
+        };
+        let mut data = WINTRUST_DATA {
+            cbStruct: std::mem::size_of::<WINTRUST_DATA>() as u32,
+            dwUIChoice: WTD_UI_NONE,
+            fdwRevocationChecks: WTD_REVOKE_NONE,
+            dwUnionChoice: WTD_CHOICE_FILE,
+            Anonymous: WINTRUST_DATA_0 { pFile: &mut file },
+            dwStateAction: WTD_STATEACTION_VERIFY,
+            ..Default::default()
+        };
+        let mut action = WINTRUST_ACTION_GENERIC_VERIFY_V2;
+        let status = WinVerifyTrust(
+            HWND::default(),
+            &mut action,
+            &mut data as *mut _ as *mut std::ffi::c_void,
+        );
+        // Release the state the verify call allocated.
+        data.dwStateAction = WTD_STATEACTION_CLOSE;
+        let _ = WinVerifyTrust(
+            HWND::default(),
+            &mut action,
+            &mut data as *mut _ as *mut std::ffi::c_void,
+        );
+        status == 0
+    }
+}
+
+// Display name of the certificate that signed the file, or None if it has
+// no embedded signature. Some("") when the signer's cert isn't in the
+// message.
+fn signer_name(wide_path: &[u16]) -> Option<String> {
+    unsafe {
+        let mut store = HCERTSTORE::default();
+        let mut msg: *mut std::ffi::c_void = std::ptr::null_mut();
+        CryptQueryObject(
+            CERT_QUERY_OBJECT_FILE,
+            wide_path.as_ptr() as *const std::ffi::c_void,
+            CERT_QUERY_CONTENT_FLAG_PKCS7_SIGNED_EMBED,
+            CERT_QUERY_FORMAT_FLAG_BINARY,
+            0,
+            None,
+            None,
+            None,
+            Some(&mut store),
+            Some(&mut msg),
+            None,
+        )
+        .ok()?;
+
+        let name = signer_from_message(store, msg).unwrap_or_default();
+        let _ = CryptMsgClose(Some(msg));
+        let _ = CertCloseStore(store, 0);
+        Some(name)
+    }
+}
+
+unsafe fn signer_from_message(store: HCERTSTORE, msg: *mut std::ffi::c_void) -> Option<String> {
+    let mut size = 0u32;
+    CryptMsgGetParam(msg, CMSG_SIGNER_INFO_PARAM, 0, None, &mut size).ok()?;
+    // u64 backing keeps the CMSG_SIGNER_INFO header suitably aligned.
+    let mut buf = vec![0u64; (size as usize).div_ceil(8)];
+    CryptMsgGetParam(
+        msg,
+        CMSG_SIGNER_INFO_PARAM,
+        0,
+        Some(buf.as_mut_ptr() as *mut std::ffi::c_void),
+        &mut size,
+    )
+    .ok()?;
+    let signer = &*(buf.as_ptr() as *const CMSG_SIGNER_INFO);
+
+    let subject = CERT_INFO {
+        Issuer: signer.Issuer,
+        SerialNumber: signer.SerialNumber,
+        ..Default::default()
+    };
+    let cert = CertFindCertificateInStore(
+        store,
+        X509_ASN_ENCODING | PKCS_7_ASN_ENCODING,
+        0,
+        CERT_FIND_SUBJECT_CERT,
+        Some(&subject as *const _ as *const std::ffi::c_void),
+        None,
+    );
+    if cert.is_null() {
+        return None;
+    }
+    let mut name = [0u16; 256];
+    let len = CertGetNameStringW(
+        cert,
+        CERT_NAME_SIMPLE_DISPLAY_TYPE,
+        0,
+        None,
+        Some(&mut name),
+    );
+    let _ = CertFreeCertificateContext(Some(cert));
+    // `len` counts the terminating NUL; 1 means an empty name.
+    let len = (len as usize).saturating_sub(1);
+    Some(String::from_utf16_lossy(&name[..len]))
+}
+
+// Whether `path` sits directly in the Windows directory or System32 /
+// SysWOW64, where the real copies of the core OS processes live.
+pub fn is_os_binary(path: &Path) -> bool {
+    let root = std::env::var("SystemRoot").unwrap_or_else(|_| "C:\\Windows".to_string());
+    let Some(dir) = path.parent() else {
+        return false;
+    };
+    let dir = dir.to_string_lossy().to_lowercase();
+    [
+        root.clone(),
+        format!("{}\\System32", root),
+        format!("{}\\SysWOW64", root),
+    ]
+    .iter()
+    .any(|candidate| dir == candidate.to_lowercase())
+}
+
+// Windows services are all started by the Service Control Manager.
+pub fn is_service_host(parent_name: &str) -> bool {
+    parent_name.eq_ignore_ascii_case("services.exe")
+}
+
+// Block until `pid` exits or `timeout_ms` elapses. Returns true if it exited.
+pub fn wait_for_exit(pid: u32, timeout_ms: u64) -> bool {
+    unsafe {
+        let handle = match OpenProcess(PROCESS_SYNCHRONIZE, false, pid) {
+            Ok(h) if !h.is_invalid() => h,
+            // Can't open it any more — most likely it's already gone.
+            _ => return true,
+        };
+        let wait = WaitForSingleObject(handle, timeout_ms.min(u32::MAX as u64) as u32);
+        let _ = CloseHandle(handle);
+        wait == WAIT_OBJECT_0
+    }
+}
+
+type NtProcessFn = unsafe extern "system" fn(HANDLE) -> NTSTATUS;
+
+// NtSuspendProcess / NtResumeProcess are undocumented but stable ntdll
+// exports; resolve them at runtime rather than linking against ntdll.lib.
+fn call_nt_process_fn(pid: u32, export: windows::core::PCSTR) -> Result<(), AppError> {
+    unsafe {
+        let ntdll = GetModuleHandleW(w!("ntdll.dll")).map_err(|e| e.to_string())?;
+        let proc_addr = GetProcAddress(ntdll, export)
+            .ok_or_else(|| AppError::Other("ntdll export not found".to_string()))?;
+        let nt_fn: NtProcessFn = std::mem::transmute(proc_addr);
+
+        let handle = OpenProcess(PROCESS_SUSPEND_RESUME, false, pid).map_err(|_| {
+            AppError::AccessDenied("Access denied. Restart as Administrator.".to_string())
+        })?;
+        let status = nt_fn(handle);
+        let _ = CloseHandle(handle);
+
+        if status.is_ok() {
+            Ok(())
+        } else {
+            Err(AppError::Other(format!("NTSTATUS 0x{:08X}", status.0)))
+        }
+    }
+}
+
+type NtQueryInformationProcessFn =
+    unsafe extern "system" fn(HANDLE, u32, *mut std::ffi::c_void, u32, *mut u32) -> NTSTATUS;
+
+// PROCESSINFOCLASS value for PROCESS_BASIC_INFORMATION.
+const PROCESS_BASIC_INFORMATION_CLASS: u32 = 0;
+
+#[repr(C)]
+#[derive(Default)]
+struct ProcessBasicInformation {
+    _exit_status: i32,
+    peb_base_address: usize,
+    _affinity_mask: usize,
+    _base_priority: i32,
+    _unique_process_id: usize,
+    _inherited_from_unique_process_id: usize,
+}
+
+// Undocumented but long-stable offsets: PEB.ProcessParameters, and
+// RTL_USER_PROCESS_PARAMETERS.Environment / EnvironmentSize.
+#[cfg(target_pointer_width = "64")]
+const PEB_PROCESS_PARAMETERS: usize = 0x20;
+#[cfg(target_pointer_width = "64")]
+const PARAMS_ENVIRONMENT: usize = 0x80;
+#[cfg(target_pointer_width = "64")]
+const PARAMS_ENVIRONMENT_SIZE: usize = 0x3F0;
+#[cfg(target_pointer_width = "32")]
+const PEB_PROCESS_PARAMETERS: usize = 0x10;
+#[cfg(target_pointer_width = "32")]
+const PARAMS_ENVIRONMENT: usize = 0x48;
+#[cfg(target_pointer_width = "32")]
+const PARAMS_ENVIRONMENT_SIZE: usize = 0x290;
+
+// Sanity cap; real environment blocks are a few KB.
+const MAX_ENVIRONMENT_BYTES: usize = 1 << 20;
+
+unsafe fn read_remote<T: Default>(handle: HANDLE, address: usize) -> Result<T, AppError> {
+    let mut value = T::default();
+    ReadProcessMemory(
+        handle,
+        address as *const std::ffi::c_void,
+        &mut value as *mut T as *mut std::ffi::c_void,
+        std::mem::size_of::<T>(),
+        None,
+    )
+    .map_err(|e| AppError::Other(format!("Failed to read process memory: {}", e)))?;
+    Ok(value)
+}
+
+// Walk PEB -> ProcessParameters -> Environment in the target and read the
+// block (UTF-16 "KEY=value" entries, each NUL-terminated).
+pub fn process_environment(pid: u32) -> Result<Vec<(String, String)>, AppError> {
+    unsafe {
+        let ntdll = GetModuleHandleW(w!("ntdll.dll")).map_err(|e| e.to_string())?;
+        let proc_addr = GetProcAddress(ntdll, s!("NtQueryInformationProcess"))
+            .ok_or_else(|| AppError::Other("ntdll export not found".to_string()))?;
+        let query: NtQueryInformationProcessFn = std::mem::transmute(proc_addr);
+
+        let handle =
+            OpenProcess(PROCESS_QUERY_INFORMATION | PROCESS_VM_READ, false, pid).map_err(|e| {
+                match api_error(pid, e) {
+                    AppError::AccessDenied(_) => AppError::AccessDenied(format!(
+                    "Access denied reading the environment of PID {}. Restart as Administrator.",
+                    pid
+                )),
+                    other => other,
+                }
+            })?;
+        let result = read_environment(handle, query);
+        let _ = CloseHandle(handle);
+        result
+    }
+}
+
+unsafe fn read_environment(
+    handle: HANDLE,
+    query: NtQueryInformationProcessFn,
+) -> Result<Vec<(String, String)>, AppError> {
+    let mut info = ProcessBasicInformation::default();
+    let status = query(
+        handle,
+        PROCESS_BASIC_INFORMATION_CLASS,
+        &mut info as *mut _ as *mut std::ffi::c_void,
+        std::mem::size_of::<ProcessBasicInformation>() as u32,
+        std::ptr::null_mut(),
+    );
+    if status.is_err() {
+        return Err(AppError::Other(format!("NTSTATUS 0x{:08X}", status.0)));
+    }
+    let params: usize = read_remote(handle, info.peb_base_address + PEB_PROCESS_PARAMETERS)?;
+    let env: usize = read_remote(handle, params + PARAMS_ENVIRONMENT)?;
+    let size: usize = read_remote(handle, params + PARAMS_ENVIRONMENT_SIZE)?;
+    let size = size.min(MAX_ENVIRONMENT_BYTES);
+
+    let mut block = vec![0u16; size / 2];
+    ReadProcessMemory(
+        handle,
+        env as *const std::ffi::c_void,
+        block.as_mut_ptr() as *mut std::ffi::c_void,
+        block.len() * 2,
+        None,
+    )
+    .map_err(|e| AppError::Other(format!("Failed to read process memory: {}", e)))?;
+    Ok(block
+        .split(|&c| c == 0)
+        .take_while(|entry| !entry.is_empty())
+        .filter_map(|entry| super::env_pair(&String::from_utf16_lossy(entry)))
+        .collect())
+}
+
+type NtQuerySystemInformationFn =
+    unsafe extern "system" fn(u32, *mut std::ffi::c_void, u32, *mut u32) -> NTSTATUS;
+type NtQueryObjectFn =
+    unsafe extern "system" fn(HANDLE, u32, *mut std::ffi::c_void, u32, *mut u32) -> NTSTATUS;
+
+// SYSTEMINFOCLASS SystemExtendedHandleInformation. The plain
+// SystemHandleInformation table truncates PIDs to 16 bits.
+const SYSTEM_EXTENDED_HANDLE_INFORMATION: u32 = 64;
+// OBJECT_INFORMATION_CLASS values.
+const OBJECT_NAME_INFORMATION: u32 = 1;
+const OBJECT_TYPE_INFORMATION: u32 = 2;
+const STATUS_INFO_LENGTH_MISMATCH: i32 = 0xC0000004_u32 as i32;
+// The handle table of a busy machine is a few MB; give up well past that.
+const MAX_HANDLE_TABLE_BYTES: usize = 256 << 20;
+// A name query that takes longer than this is stuck behind a synchronous
+// pipe read in the owning process, and would stay stuck.
+const HANDLE_QUERY_TIMEOUT: Duration = Duration::from_millis(200);
+
+#[repr(C)]
+struct SystemHandleEntry {
+    _object: usize,
+    unique_process_id: usize,
+    handle_value: usize,
+    _granted_access: u32,
+    _creator_back_trace_index: u16,
+    object_type_index: u16,
+    _handle_attributes: u32,
+    _reserved: u32,
+}
+
+#[repr(C)]
+struct UnicodeString {
+    length: u16,
+    _maximum_length: u16,
+    buffer: *const u16,
+}
+
+fn ntdll_export(
+    name: windows::core::PCSTR,
+) -> Result<unsafe extern "system" fn() -> isize, AppError> {
+    unsafe {
+        let ntdll = GetModuleHandleW(w!("ntdll.dll")).map_err(|e| e.to_string())?;
+        GetProcAddress(ntdll, name)
+            .ok_or_else(|| AppError::Other("ntdll export not found".to_string()))
+    }
+}
+
+// Every process holding a socket handle bound to `port`. Sockets are AFD
+// file handles: each one in the system handle table is duplicated into our
+// process, checked for the \Device\Afd name and asked for its local port.
+// Processes we can't open for PROCESS_DUP_HANDLE (elevated or other users'
+// unless we're elevated too) are skipped.
+pub fn socket_handle_holders(port: u16) -> Result<Vec<u32>, AppError> {
+    unsafe {
+        let query_system: NtQuerySystemInformationFn =
+            std::mem::transmute(ntdll_export(s!("NtQuerySystemInformation"))?);
+        let query_object: NtQueryObjectFn = std::mem::transmute(ntdll_export(s!("NtQueryObject"))?);
+        let entries = handle_table(query_system)?;
+
+        let mut wsa = WSADATA::default();
+        if WSAStartup(0x202, &mut wsa) != 0 {
+            return Err(AppError::Other("Failed to initialise Winsock".to_string()));
+        }
+        let holders = find_afd_holders(query_object, &entries, port);
+        WSACleanup();
+        Ok(holders)
+    }
+}
+
+// (PID, handle value, object type index) of every open handle except ours.
+unsafe fn handle_table(
+    query: NtQuerySystemInformationFn,
+) -> Result<Vec<(u32, usize, u16)>, AppError> {
+    // u64 words keep the table 8-byte aligned.
+    let mut buffer: Vec<u64> = vec![0; (1 << 20) / 8];
+    loop {
+        let mut needed = 0u32;
+        let status = query(
+            SYSTEM_EXTENDED_HANDLE_INFORMATION,
+            buffer.as_mut_ptr() as *mut std::ffi::c_void,
+            (buffer.len() * 8) as u32,
+            &mut needed,
+        );
+        if status.0 == STATUS_INFO_LENGTH_MISMATCH {
+            // Handles come and go between calls, so leave some slack.
+            let bytes = (needed as usize).max(buffer.len() * 8) * 2;
+            if bytes > MAX_HANDLE_TABLE_BYTES {
+                return Err(AppError::Other("System handle table too large".to_string()));
+            }
+            buffer = vec![0; bytes / 8];
+            continue;
+        }
+        if status.is_err() {
+            return Err(AppError::Other(format!("NTSTATUS 0x{:08X}", status.0)));
+        }
+        break;
+    }
+
+    // Header: NumberOfHandles, Reserved, then the entries.
+    let header = buffer.as_ptr() as *const usize;
+    let capacity = (buffer.len() * 8 - 2 * std::mem::size_of::<usize>())
+        / std::mem::size_of::<SystemHandleEntry>();
+    let count = (*header).min(capacity);
+    let entries = std::slice::from_raw_parts(header.add(2) as *const SystemHandleEntry, count);
+    let own = GetCurrentProcessId();
+    Ok(entries
+        .iter()
+        .map(|e| {
+            (
+                e.unique_process_id as u32,
+                e.handle_value,
+                e.object_type_index,
+            )
+        })
+        .filter(|&(pid, _, _)| pid != own)
+        .collect())
+}
+
+unsafe fn find_afd_holders(
+    query: NtQueryObjectFn,
+    entries: &[(u32, usize, u16)],
+    port: u16,
+) -> Vec<u32> {
+    let mut processes: HashMap<u32, Option<HANDLE>> = HashMap::new();
+    // Object type index -> whether it's "File"; the indexes vary by build.
+    let mut file_types: HashMap<u16, bool> = HashMap::new();
+    let mut holders = BTreeSet::new();
+    let mut probe = spawn_afd_probe(query);
+
+    for &(pid, value, type_index) in entries {
+        if holders.contains(&pid) || file_types.get(&type_index) == Some(&false) {
+            continue;
+        }
+        let process = *processes
+            .entry(pid)
+            .or_insert_with(|| OpenProcess(PROCESS_DUP_HANDLE, false, pid).ok());
+        let Some(process) = process else {
+            continue;
+        };
+        let mut handle = HANDLE::default();
+        if DuplicateHandle(
+            process,
+            HANDLE(value as *mut std::ffi::c_void),
+            GetCurrentProcess(),
+            &mut handle,
+            0,
+            false,
+            DUPLICATE_SAME_ACCESS,
+        )
+        .is_err()
+        {
+            continue;
+        }
+        // Type queries never block, unlike name queries.
+        let is_file = *file_types.entry(type_index).or_insert_with(|| {
+            object_string(query, handle, OBJECT_TYPE_INFORMATION).as_deref() == Some("File")
+        });
+        if !is_file {
+            let _ = CloseHandle(handle);
+            continue;
+        }
+
+        let _ = probe.0.send(handle.0 as isize);
+        match probe.1.recv_timeout(HANDLE_QUERY_TIMEOUT) {
+            Ok(bound) => {
+                if bound == Some(port) {
+                    holders.insert(pid);
+                }
+                let _ = CloseHandle(handle);
+            }
+            // The worker is wedged on this handle for good; leave both to
+            // it and carry on with a fresh one.
+            Err(_) => probe = spawn_afd_probe(query),
+        }
+    }
+
+    for handle in processes.into_values().flatten() {
+        let _ = CloseHandle(handle);
+    }
+    holders.into_iter().collect()
+}
+
+// Worker that maps a duplicated handle to the local port of the socket
+// behind it, or None if it isn't a socket. Runs apart from the caller so a
+// name query that blocks can be abandoned.
+fn spawn_afd_probe(query: NtQueryObjectFn) -> (mpsc::Sender<isize>, mpsc::Receiver<Option<u16>>) {
+    let (job_tx, job_rx) = mpsc::channel::<isize>();
+    let (result_tx, result_rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for raw in job_rx {
+            let port = unsafe { afd_port(query, HANDLE(raw as *mut std::ffi::c_void)) };
+            if result_tx.send(port).is_err() {
+                break;
+            }
+        }
+    });
+    (job_tx, result_rx)
+}
+
+unsafe fn afd_port(query: NtQueryObjectFn, handle: HANDLE) -> Option<u16> {
+    let name = object_string(query, handle, OBJECT_NAME_INFORMATION)?;
+    if !name.to_lowercase().starts_with("\\device\\afd") {
+        return None;
+    }
+    let mut addr = SOCKADDR_STORAGE::default();
+    let mut len = std::mem::size_of::<SOCKADDR_STORAGE>() as i32;
+    if getsockname(
+        SOCKET(handle.0 as usize),
+        &mut addr as *mut SOCKADDR_STORAGE as *mut SOCKADDR,
+        &mut len,
+    ) != 0
+    {
+        return None;
+    }
+    // sin_port and sin6_port sit at the same offset.
+    let sin = &*(&addr as *const SOCKADDR_STORAGE as *const SOCKADDR_IN);
+    Some(u16::from_be(sin.sin_port))
+}
+
+// OBJECT_NAME_INFORMATION and OBJECT_TYPE_INFORMATION both start with the
+// UNICODE_STRING we want.
+unsafe fn object_string(query: NtQueryObjectFn, handle: HANDLE, class: u32) -> Option<String> {
+    let mut buffer: Vec<u64> = vec![0; 256];
+    let status = query(
+        handle,
+        class,
+        buffer.as_mut_ptr() as *mut std::ffi::c_void,
+        (buffer.len() * 8) as u32,
+        std::ptr::null_mut(),
+    );
+    if status.is_err() {
+        return None;
+    }
+    let string = &*(buffer.as_ptr() as *const UnicodeString);
+    if string.buffer.is_null() {
+        return None;
+    }
+    let chars = std::slice::from_raw_parts(string.buffer, string.length as usize / 2);
+    Some(String::from_utf16_lossy(chars))
+}
+
+pub fn suspend(pid: u32) -> Result<(), AppError> {
+    call_nt_process_fn(pid, s!("NtSuspendProcess"))
+}
+
+pub fn resume(pid: u32) -> Result<(), AppError> {
+    call_nt_process_fn(pid, s!("NtResumeProcess"))
+}
+
+pub fn set_priority(pid: u32, priority: super::Priority) -> Result<(), AppError> {
+    use super::Priority;
+    let class = match priority {
+        Priority::Idle => IDLE_PRIORITY_CLASS,
+        Priority::BelowNormal => BELOW_NORMAL_PRIORITY_CLASS,
+        Priority::Normal => NORMAL_PRIORITY_CLASS,
+        Priority::AboveNormal => ABOVE_NORMAL_PRIORITY_CLASS,
+        Priority::High => HIGH_PRIORITY_CLASS,
+    };
+    unsafe {
+        let handle = OpenProcess(PROCESS_SET_INFORMATION, false, pid).map_err(|_| {
+            AppError::AccessDenied("Access denied. Restart as Administrator.".to_string())
+        })?;
+        let result = SetPriorityClass(handle, class);
+        let _ = CloseHandle(handle);
+        result.map_err(|e| AppError::Other(format!("Failed to set priority: {}", e)))
+    }
+}
+
+// Parse `netsh int ipv4 show excludedportrange protocol=<tcp|udp>`. Rows are
+// "<start> <end>" with an optional trailing "*" for administered exclusions;
+// headers and the legend don't start with two numbers and are skipped.
+pub fn reserved_port_ranges(protocol: &str) -> Result<Vec<(u16, u16)>, String> {
+    let output = Command::new("netsh")
+        .creation_flags(CREATE_NO_WINDOW)
+        .args([
+            "int",
+            "ipv4",
+            "show",
+            "excludedportrange",
+            &format!("protocol={}", protocol),
+        ])
+        .output()
+        .map_err(|e| format!("Failed to execute netsh: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "netsh failed: {}",
+            String::from_utf8_lossy(&output.stdout).trim()
+        ));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let start = parts.next()?.parse::<u16>().ok()?;
+            let end = parts.next()?.parse::<u16>().ok()?;
+            Some((start, end))
+        })
+        .collect())
+}
+
+pub fn open_task_manager() -> Result<(), String> {
+    Command::new("taskmgr.exe")
+        .creation_flags(CREATE_NO_WINDOW)
+        .spawn()
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+// Open Explorer with `path` selected, like Task Manager's "Open file location".
+pub fn reveal_in_file_manager(path: &Path) -> Result<(), String> {
+    // Explorer parses its own command line, so the quotes around the path
+    // must reach it verbatim — `arg` would escape them.
+    Command::new("explorer.exe")
+        .creation_flags(CREATE_NO_WINDOW)
+        .raw_arg(format!("/select,\"{}\"", path.display()))
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+// Quote `arg` for a child's command line, the way CommandLineToArgvW (and
+// so Rust's std::env::args) splits it: backslashes are literal except
+// before a quote, where they have to be doubled.
+fn quote_command_line_arg(arg: &str) -> String {
+    let mut quoted = String::from('"');
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                quoted.push_str(&"\\".repeat(backslashes * 2 + 1));
+                backslashes = 0;
+            }
+            _ => {
+                quoted.push_str(&"\\".repeat(backslashes));
+                backslashes = 0;
+            }
+        }
+        if c != '\\' {
+            quoted.push(c);
+        }
+    }
+    quoted.push_str(&"\\".repeat(backslashes * 2));
+    quoted.push('"');
+    quoted
+}
+
+// Relaunch `exe` with `args` through the UAC prompt.
+pub fn spawn_elevated(exe: &Path, args: &[String]) -> Result<(), String> {
+    let quote = |s: &str| format!("'{}'", s.replace('\'', "''"));
+    let mut script = format!(
+        "Start-Process -FilePath {} -Verb RunAs",
+        quote(&exe.to_string_lossy())
+    );
+    // Start-Process joins -ArgumentList with bare spaces, so an argument
+    // containing one (a %TEMP% under "C:\Users\Jane Doe") would be split.
+    // Pass one string that's already quoted for the child instead.
+    if !args.is_empty() {
+        let line: Vec<String> = args.iter().map(|a| quote_command_line_arg(a)).collect();
+        script.push_str(&format!(" -ArgumentList {}", quote(&line.join(" "))));
+    }
+
+    // Use ShellExecuteW via PowerShell to properly elevate
+    Command::new("powershell")
+        .creation_flags(CREATE_NO_WINDOW) // hide PowerShell window
+        .args(["-WindowStyle", "Hidden", "-Command", &script])
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+const RUN_KEY: PCWSTR = w!("Software\\Microsoft\\Windows\\CurrentVersion\\Run");
+const RUN_VALUE: PCWSTR = w!("PortKiller");
+
+fn open_run_key(access: REG_SAM_FLAGS) -> Result<HKEY, String> {
+    let mut key = HKEY::default();
+    unsafe { RegOpenKeyExW(HKEY_CURRENT_USER, RUN_KEY, 0, access, &mut key) }
+        .ok()
+        .map_err(|e| e.to_string())?;
+    Ok(key)
+}
+
+// Launch `exe <args>` at login via the per-user Run key. Disabling deletes the
+// value outright; an empty value would still show up in Startup apps.
+pub fn set_autostart(exe: &Path, args: &str, enabled: bool) -> Result<(), String> {
+    let key = open_run_key(KEY_SET_VALUE)?;
+    let result = unsafe {
+        if enabled {
+            let command = format!("\"{}\" {}\0", exe.display(), args);
+            let bytes: Vec<u8> = command
+                .encode_utf16()
+                .flat_map(|unit| unit.to_le_bytes())
+                .collect();
+            RegSetValueExW(key, RUN_VALUE, 0, REG_SZ, Some(&bytes))
+        } else {
+            match RegDeleteValueW(key, RUN_VALUE) {
+                ERROR_FILE_NOT_FOUND => ERROR_SUCCESS,
+                other => other,
+            }
+        }
+    };
+    unsafe {
+        let _ = RegCloseKey(key);
+    }
+    result.ok().map_err(|e| e.to_string())
+}
+
+pub fn is_autostart_enabled() -> bool {
+    let Ok(key) = open_run_key(KEY_QUERY_VALUE) else {
+        return false;
+    };
+    unsafe {
+        let found = RegQueryValueExW(key, RUN_VALUE, None, None, None, None).is_ok();
+        let _ = RegCloseKey(key);
+        found
+    }
+}
+
+const SERVICES_KEY: PCWSTR = w!("SYSTEM\\CurrentControlSet\\Services");
+
+// (service name, ImagePath) for every service and driver the Service
+// Control Manager knows about. Entries without an ImagePath are skipped.
+pub fn service_command_lines() -> Result<Vec<(String, String)>, String> {
+    let mut key = HKEY::default();
+    unsafe { RegOpenKeyExW(HKEY_LOCAL_MACHINE, SERVICES_KEY, 0, KEY_READ, &mut key) }
+        .ok()
+        .map_err(|e| e.to_string())?;
+
+    let mut services = Vec::new();
+    for index in 0u32.. {
+        let mut name = [0u16; 256];
+        let mut name_len = name.len() as u32;
+        let status = unsafe {
+            RegEnumKeyExW(
+                key,
+                index,
+                PWSTR(name.as_mut_ptr()),
+                &mut name_len,
+                None,
+                PWSTR::null(),
+                None,
+                None,
+            )
+        };
+        if status == ERROR_NO_MORE_ITEMS {
+            break;
+        }
+        if status.is_err() {
+            continue;
+        }
+        let name = String::from_utf16_lossy(&name[..name_len as usize]);
+        if let Some(image_path) = read_string_value(key, &name, w!("ImagePath")) {
+            services.push((name, image_path));
+        }
+    }
+    unsafe {
+        let _ = RegCloseKey(key);
+    }
+    Ok(services)
+}
+
+// A REG_SZ / REG_EXPAND_SZ value under `key\subkey`, with environment
+// variables expanded.
+fn read_string_value(key: HKEY, subkey: &str, value: PCWSTR) -> Option<String> {
+    let subkey: Vec<u16> = subkey.encode_utf16().chain(std::iter::once(0)).collect();
+    let mut buf = vec![0u16; 2048];
+    let mut size = (buf.len() * 2) as u32;
+    unsafe {
+        RegGetValueW(
+            key,
+            PCWSTR(subkey.as_ptr()),
+            value,
+            RRF_RT_REG_SZ,
+            None,
+            Some(buf.as_mut_ptr().cast()),
+            Some(&mut size),
+        )
+    }
+    .ok()
+    .ok()?;
+    // `size` is in bytes and counts the terminating NUL.
+    let len = (size as usize / 2).saturating_sub(1);
+    Some(String::from_utf16_lossy(&buf[..len]))
+}
+
+// Tear down an IPv4 TCP connection by setting its table entry to
+// DELETE_TCB, which sends an RST. The IP Helper API only exposes this for
+// IPv4 and only to administrators.
+pub fn reset_tcp_connection(local: SocketAddrV4, remote: SocketAddrV4) -> Result<(), AppError> {
+    let row = MIB_TCPROW_LH {
+        Anonymous: MIB_TCPROW_LH_0 {
+            State: MIB_TCP_STATE_DELETE_TCB,
+        },
+        // Addresses and ports are in network byte order.
+        dwLocalAddr: u32::from_ne_bytes(local.ip().octets()),
+        dwLocalPort: local.port().to_be() as u32,
+        dwRemoteAddr: u32::from_ne_bytes(remote.ip().octets()),
+        dwRemotePort: remote.port().to_be() as u32,
+    };
+    match unsafe { SetTcpEntry(&row) } {
+        0 => Ok(()),
+        code if code == ERROR_ACCESS_DENIED.0 => Err(AppError::AccessDenied(
+            "Access denied. Resetting connections requires Administrator.".to_string(),
+        )),
+        code => Err(AppError::Other(format!(
+            "SetTcpEntry failed with error {}",
+            code
+        ))),
+    }
+}
+
+// GetExtendedTcpTable has no queue columns, and the per-connection ESTATS
+// counters have to be switched on per connection (as Administrator) before
+// they collect anything, so Windows reports no queues.
+pub fn tcp_queues() -> super::TcpQueues {
+    super::TcpQueues::new()
+}
+
+// Unicast addresses of every adapter that's up. Empty if the adapter list
+// can't be read.
+pub fn local_addresses() -> Vec<IpAddr> {
+    let flags = GAA_FLAG_SKIP_ANYCAST | GAA_FLAG_SKIP_MULTICAST | GAA_FLAG_SKIP_DNS_SERVER;
+    let mut size = 16 * 1024u32;
+    // u64 elements keep the buffer aligned for IP_ADAPTER_ADDRESSES_LH.
+    let mut buf: Vec<u64> = Vec::new();
+    // The adapter list can grow between the size query and the real call.
+    for _ in 0..3 {
+        buf = vec![0u64; (size as usize).div_ceil(8)];
+        let status = unsafe {
+            GetAdaptersAddresses(
+                AF_UNSPEC.0 as u32,
+                flags,
+                None,
+                Some(buf.as_mut_ptr() as *mut IP_ADAPTER_ADDRESSES_LH),
+                &mut size,
+            )
+        };
+        match status {
+            0 => break,
+            code if code == ERROR_BUFFER_OVERFLOW.0 => continue,
+            _ => return Vec::new(),
+        }
+    }
+
+    let mut addresses = Vec::new();
+    let mut adapter = buf.as_ptr() as *const IP_ADAPTER_ADDRESSES_LH;
+    unsafe {
+        while !adapter.is_null() {
+            if (*adapter).OperStatus == IfOperStatusUp {
+                let mut unicast = (*adapter).FirstUnicastAddress;
+                while !unicast.is_null() {
+                    if let Some(ip) = sockaddr_ip((*unicast).Address.lpSockaddr) {
+                        addresses.push(ip);
+                    }
+                    unicast = (*unicast).Next;
+                }
+            }
+            adapter = (*adapter).Next;
+        }
+    }
+    addresses
+}
+
+unsafe fn sockaddr_ip(addr: *const SOCKADDR) -> Option<IpAddr> {
+    if addr.is_null() {
+        return None;
+    }
+    match (*addr).sa_family {
+        AF_INET => {
+            let v4 = &*(addr as *const SOCKADDR_IN);
+            Some(IpAddr::V4(Ipv4Addr::from(u32::from_be(
+                v4.sin_addr.S_un.S_addr,
+            ))))
+        }
+        AF_INET6 => {
+            let v6 = &*(addr as *const SOCKADDR_IN6);
+            Some(IpAddr::V6(Ipv6Addr::from(v6.sin6_addr.u.Byte)))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,172 @@
+// Headless JSON-RPC mode for editor integrations. `--rpc` skips the window
+// entirely: each line on stdin is a request like
+// `{"id":1,"method":"kill_by_port","params":{"port":3000}}` and gets exactly
+// one response line on stdout, `{"id":1,"result":...}` or
+// `{"id":1,"error":{"kind":...,"message":...}}`.
+
+use crate::error::AppError;
+use crate::{
+    collect_ports, find_listener, get_kill_command, kill_pid, kill_port, listening_states,
+    load_kill_history, load_name_list, normalize_protocol, probe_port, AppData, KillOptions,
+    ScanOptions, FAVORITES_FILE, IGNORE_LIST_FILE, KILL_HISTORY_FILE, PROTECTED_LIST_FILE,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+
+pub const RPC_ARG: &str = "--rpc";
+
+// Must match `identifier` in tauri.conf.json; Tauri nests its config and
+// data dirs under it, and without an AppHandle we resolve them ourselves.
+const APP_IDENTIFIER: &str = "com.portkiller.app";
+
+#[derive(Deserialize)]
+struct RpcRequest {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Deserialize)]
+struct ListParams {
+    #[serde(default)]
+    options: ScanOptions,
+}
+
+#[derive(Deserialize)]
+struct PortParams {
+    port: u16,
+    protocol: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct KillParams {
+    pid: u32,
+    port: u16,
+    process_name: String,
+    #[serde(default)]
+    options: KillOptions,
+}
+
+#[derive(Deserialize)]
+struct KillPortParams {
+    port: u16,
+    protocol: Option<String>,
+    #[serde(default)]
+    options: KillOptions,
+}
+
+#[derive(Deserialize)]
+struct KillCommandParams {
+    pid: u32,
+    shell: Option<String>,
+}
+
+pub fn requested() -> bool {
+    std::env::args().any(|arg| arg == RPC_ARG)
+}
+
+// Serve requests until stdin closes (or stdout does).
+pub fn run(data: AppData) {
+    load_persisted(&data);
+
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout().lock();
+    for line in stdin.lock().lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<RpcRequest>(&line) {
+            Ok(request) => {
+                let id = request.id.clone();
+                match dispatch(&data, request) {
+                    Ok(result) => json!({ "id": id, "result": result }),
+                    Err(e) => json!({ "id": id, "error": e }),
+                }
+            }
+            Err(e) => json!({
+                "id": Value::Null,
+                "error": AppError::InvalidInput(format!("Malformed request: {}", e)),
+            }),
+        };
+        if writeln!(stdout, "{}", response)
+            .and_then(|_| stdout.flush())
+            .is_err()
+        {
+            break;
+        }
+    }
+}
+
+// The GUI loads these in its setup hook; kills made over RPC must respect
+// the user's protected list and land in the same audit log, and listings
+// hide the same ignored processes and order favorites the same way.
+fn load_persisted(data: &AppData) {
+    if let Some(dir) = dirs::config_dir() {
+        let dir = dir.join(APP_IDENTIFIER);
+        if let Ok(mut set) = data.protected.lock() {
+            set.extend(load_name_list(&dir.join(PROTECTED_LIST_FILE)));
+        }
+        if let Ok(mut set) = data.ignored.lock() {
+            set.extend(load_name_list(&dir.join(IGNORE_LIST_FILE)));
+        }
+        if let Ok(mut set) = data.favorites.lock() {
+            set.extend(load_name_list(&dir.join(FAVORITES_FILE)));
+        }
+    }
+    if let Some(dir) = dirs::data_dir() {
+        let path = dir.join(APP_IDENTIFIER).join(KILL_HISTORY_FILE);
+        if let Ok(mut history) = data.kill_history.lock() {
+            *history = load_kill_history(&path);
+        }
+        let _ = data.history_path.set(path);
+    }
+}
+
+fn dispatch(data: &AppData, request: RpcRequest) -> Result<Value, AppError> {
+    match request.method.as_str() {
+        "get_listening_ports" => {
+            let p: ListParams = params(request.params)?;
+            reply(collect_ports(data, &listening_states(), &p.options)?)
+        }
+        "check_port" => {
+            let p: PortParams = params(request.params)?;
+            let protocol = p.protocol.as_deref().map(normalize_protocol).transpose()?;
+            reply(find_listener(data, p.port, protocol)?)
+        }
+        "probe_port" => {
+            let p: PortParams = params(request.params)?;
+            reply(probe_port(p.port, p.protocol)?)
+        }
+        "kill_process" => {
+            let p: KillParams = params(request.params)?;
+            reply(kill_pid(data, p.pid, p.port, p.process_name, &p.options))
+        }
+        "kill_by_port" => {
+            let p: KillPortParams = params(request.params)?;
+            reply(kill_port(data, p.port, p.protocol.as_deref(), &p.options))
+        }
+        "get_kill_command" => {
+            let p: KillCommandParams = params(request.params)?;
+            reply(get_kill_command(p.pid, p.shell)?)
+        }
+        other => Err(AppError::InvalidInput(format!("Unknown method: {}", other))),
+    }
+}
+
+// A request without `params` is treated as `{}`, so methods whose fields
+// are all optional can omit it.
+fn params<T: DeserializeOwned>(value: Value) -> Result<T, AppError> {
+    let value = if value.is_null() { json!({}) } else { value };
+    serde_json::from_value(value).map_err(|e| AppError::InvalidInput(e.to_string()))
+}
+
+fn reply<T: Serialize>(value: T) -> Result<Value, AppError> {
+    serde_json::to_value(value).map_err(|e| AppError::Other(e.to_string()))
+}
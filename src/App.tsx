@@ -6,7 +6,7 @@ import { getCurrentWindow, LogicalPosition, LogicalSize, availableMonitors } fro
 // current window via getCurrentWindow(). One module-level call keeps things
 // drop-in compatible with the v1 code below.
 const appWindow = getCurrentWindow()
-import type { AppState, PortInfo, KillResult, ChangeState } from './types'
+import type { AppState, PortInfo, KillResult, ChangeState, ViewState } from './types'
 import {
   COMMON_PORTS,
   loadCustomPorts,
@@ -16,6 +16,9 @@ import {
   loadKillHistory,
   appendKillHistory,
   clearKillHistory,
+  isAppError,
+  errorMessage,
+  baseProtocol,
 } from './types'
 import type { KillRecord } from './types'
 import type { Preferences } from './preferences'
@@ -78,16 +81,17 @@ function timeAgo(timestamp: number): string {
 
 // Provide actionable error guidance (H9)
 function formatErrorMessage(err: unknown, isAdmin: boolean): string {
-  const msg = err instanceof Error ? err.message : String(err)
-  if (msg.includes('Access is denied') || msg.includes('os error 5')) {
-    return isAdmin
-      ? 'Access denied even as Admin. This process is protected by the OS.'
-      : 'Access denied. Try running as Administrator (type "admin").'
-  }
-  if (msg.includes('process has exited') || msg.includes('not found')) {
-    return 'Process has already exited. Refreshing port list...'
+  if (isAppError(err)) {
+    if (err.kind === 'AccessDenied') {
+      return isAdmin
+        ? 'Access denied even as Admin. This process is protected by the OS.'
+        : 'Access denied. Try running as Administrator (type "admin").'
+    }
+    if (err.kind === 'NotFound') {
+      return 'Process has already exited. Refreshing port list...'
+    }
   }
-  return msg
+  return errorMessage(err)
 }
 
 export function App() {
@@ -168,7 +172,10 @@ export function App() {
   // Stable fetchPorts using refs to avoid stale closures
   const fetchPorts = useCallback(async () => {
     try {
-      const data = await invoke<AppState>('get_listening_ports')
+      // Dual-stack listeners show once, with both bind addresses.
+      const data = await invoke<AppState>('get_listening_ports', {
+        options: { collapse_families: true },
+      })
       const prevPorts = prevPortsRef.current
       const hadPorts = prevPorts.size > 0
 
@@ -220,7 +227,7 @@ export function App() {
       setError(null)
       setLastUpdated(Date.now())
     } catch (err) {
-      setError(err instanceof Error ? err.message : String(err))
+      setError(errorMessage(err))
     } finally {
       setLoading(false)
     }
@@ -398,7 +405,7 @@ export function App() {
 
     const byProtocol = protocolFilter === 'all'
       ? sorted
-      : sorted.filter(p => p.protocol.toUpperCase() === protocolFilter.toUpperCase())
+      : sorted.filter(p => baseProtocol(p.protocol) === protocolFilter.toUpperCase())
 
     const range = searchQuery ? parsePortRange(searchQuery) : null
     const base = !searchQuery
@@ -411,19 +418,20 @@ export function App() {
             .sort((a, b) => b.score - a.score)
             .map(({ port }) => port)
 
-    // Sticky-sort pinned ports to the top while preserving the inner ordering
-    // (search relevance when searching, port number otherwise).
-    if (pinnedPorts.size === 0) return base
+    // Sticky-sort pinned ports, then favorite processes' rows, to the top
+    // while preserving the inner ordering (search relevance when searching,
+    // port number otherwise).
     const pinned: PortInfo[] = []
+    const favorites: PortInfo[] = []
     const rest: PortInfo[] = []
-    base.forEach(p => (pinnedPorts.has(p.port) ? pinned : rest).push(p))
-    return [...pinned, ...rest]
+    base.forEach(p => (pinnedPorts.has(p.port) ? pinned : p.is_favorite ? favorites : rest).push(p))
+    return [...pinned, ...favorites, ...rest]
   }, [state?.ports, searchQuery, protocolFilter, sortMode, pinnedPorts])
 
   const protocolCounts = useMemo(() => {
     const counts = { tcp: 0, udp: 0 }
     state?.ports.forEach(p => {
-      const proto = p.protocol.toUpperCase()
+      const proto = baseProtocol(p.protocol)
       if (proto === 'TCP') counts.tcp++
       else if (proto === 'UDP') counts.udp++
     })
@@ -432,6 +440,33 @@ export function App() {
 
   useEffect(() => { filteredPortsRef.current = filteredPorts }, [filteredPorts])
 
+  // After restart_as_admin, pick up the view the previous instance handed
+  // over. The selected port is applied once it shows up in the list.
+  const restoredPortRef = useRef<number | null>(null)
+  useEffect(() => {
+    invoke<ViewState | null>('take_restore_view')
+      .then(view => {
+        if (!view) return
+        setSearchQuery(view.search_query)
+        const filter = view.protocol_filter
+        if (filter === 'all' || filter === 'tcp' || filter === 'udp') {
+          updatePreferences({ protocolFilter: filter })
+        }
+        restoredPortRef.current = view.selected_port
+      })
+      .catch(() => {})
+  }, [updatePreferences])
+
+  useEffect(() => {
+    const port = restoredPortRef.current
+    if (port === null) return
+    const idx = filteredPorts.findIndex(p => p.port === port)
+    if (idx >= 0) {
+      setSelectedIndex(idx)
+      restoredPortRef.current = null
+    }
+  }, [filteredPorts])
+
   // Drop selections for ports that have left the list (process exited or
   // filtered out). Without this, the bulk-kill button keeps an inflated count
   // and can confirm a kill against rows the user can no longer see.
@@ -667,12 +702,19 @@ export function App() {
   const executeKill = useCallback(async (portInfo: PortInfo) => {
     setKillingPort(portInfo.port)
     try {
-      const result = await invoke<KillResult>('kill_process', {
+      const kill = (confirmed: boolean) => invoke<KillResult>('kill_process', {
         pid: portInfo.pid,
         port: portInfo.port,
         processName: portInfo.process_name,
+        options: { confirmed },
       })
 
+      let result = await kill(false)
+      // High-risk targets (system binaries) need an explicit second yes.
+      if (result.needs_confirmation && window.confirm(`${result.message}?`)) {
+        result = await kill(true)
+      }
+
       if (result.success) {
         showToast(result.message, 'success')
         setKillHistory(prev => appendKillHistory({
@@ -683,13 +725,15 @@ export function App() {
         }, prev))
         setTimeout(fetchPorts, 500)
       } else {
-        showToast(formatErrorMessage(result.message, state?.is_admin ?? false), 'error')
+        showToast(formatErrorMessage(result.error ?? result.message, state?.is_admin ?? false), 'error')
+        if (result.error?.kind === 'NotFound') {
+          setTimeout(fetchPorts, 500)
+        }
       }
     } catch (err) {
       showToast(formatErrorMessage(err, state?.is_admin ?? false), 'error')
       // If process not found, auto-refresh
-      const msg = err instanceof Error ? err.message : String(err)
-      if (msg.includes('not found') || msg.includes('exited')) {
+      if (isAppError(err) && err.kind === 'NotFound') {
         setTimeout(fetchPorts, 500)
       }
     } finally {
@@ -764,20 +808,34 @@ export function App() {
   }, [selectedPorts, fetchPorts, showToast])
 
   const handleRestartAsAdmin = useCallback(async () => {
+    // Hand the current view to the elevated instance so it reopens on it.
+    const selected = filteredPortsRef.current[selectedIndex]
+    const view: ViewState = {
+      search_query: searchQuery,
+      protocol_filter: protocolFilter,
+      selected_port: selected ? selected.port : null,
+    }
     try {
       showToast('Restarting as Administrator...', 'success')
-      await invoke('restart_as_admin')
+      await invoke('restart_as_admin', { view })
     } catch (err) {
-      showToast('Failed to restart as admin: ' + (err instanceof Error ? err.message : String(err)), 'error')
+      showToast('Failed to restart as admin: ' + errorMessage(err), 'error')
     }
-  }, [showToast])
+  }, [showToast, searchQuery, protocolFilter, selectedIndex])
 
-  const handleExport = useCallback(async (format: 'json' | 'csv') => {
+  const handleExport = useCallback(async (format: 'json' | 'csv' | 'markdown') => {
     const currentState = stateRef.current
     if (!currentState) return
 
     let content: string
-    if (format === 'json') {
+    if (format === 'markdown') {
+      try {
+        content = await invoke<string>('export_ports_markdown')
+      } catch (err) {
+        showToast(errorMessage(err), 'error')
+        return
+      }
+    } else if (format === 'json') {
       content = JSON.stringify(currentState.ports, null, 2)
     } else {
       const headers = 'Port,PID,Protocol,Process,Path,Protected'
@@ -788,7 +846,7 @@ export function App() {
     }
 
     await navigator.clipboard.writeText(content)
-    showToast(`Copied ${currentState.ports.length} ports as ${format.toUpperCase()}`, 'success')
+    showToast(`Copied ${currentState.ports.length} ports as ${format === 'markdown' ? 'Markdown' : format.toUpperCase()}`, 'success')
   }, [showToast])
 
   const executeCommand = useCallback((cmd: string): boolean => {
@@ -899,7 +957,7 @@ export function App() {
       }
     }
     if (trimmed.startsWith('export')) {
-      handleExport(trimmed.includes('csv') ? 'csv' : 'json')
+      handleExport(trimmed.includes('csv') ? 'csv' : /\b(md|markdown)\b/.test(trimmed) ? 'markdown' : 'json')
       return true
     }
 
@@ -1208,6 +1266,14 @@ export function App() {
             >
               Copy CSV
             </button>
+            <button
+              onClick={() => handleExport('markdown')}
+              className="text-gray-400 hover:text-white text-[11px] transition-colors px-2 py-0.5 rounded hover:bg-dark-700 focus:outline-none focus:ring-1 focus:ring-accent-blue/40"
+              title="Copy listening ports to clipboard as a Markdown table"
+              aria-label="Export ports as a Markdown table to clipboard"
+            >
+              Copy MD
+            </button>
           </div>
         </div>
 
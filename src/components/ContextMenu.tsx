@@ -2,6 +2,7 @@ import type { JSX } from 'preact'
 import { useEffect, useRef, useState } from 'preact/hooks'
 import { open as openShell } from '@tauri-apps/plugin-shell'
 import type { PortInfo } from '../types'
+import { baseProtocol } from '../types'
 import { Icons } from './Icons'
 
 interface ContextMenuProps {
@@ -67,7 +68,7 @@ export function ContextMenu({ x, y, port, isPinned, siblingPortCount = 0, onClos
     }
 
     // Any TCP port may be reachable via http://localhost — let the user decide.
-    if (port.protocol.toUpperCase() === 'TCP') {
+    if (baseProtocol(port.protocol) === 'TCP') {
         const httpsPorts = new Set([443, 4443, 5443, 8443])
         const scheme = httpsPorts.has(port.port) ? 'https' : 'http'
         const url = `${scheme}://localhost:${port.port}`
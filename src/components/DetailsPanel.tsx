@@ -3,6 +3,7 @@ import { useState, useEffect, useRef } from 'preact/hooks'
 import { invoke } from '@tauri-apps/api/core'
 import { open as openShell } from '@tauri-apps/plugin-shell'
 import type { PortInfo, ProcessDetails } from '../types'
+import { baseProtocol } from '../types'
 import { Icons } from './Icons'
 import { useFocusTrap } from '../hooks/useFocusTrap'
 
@@ -93,7 +94,7 @@ export function DetailsPanel({ port, onClose, onKill, onCopy }: DetailsPanelProp
         try { await openShell(`${scheme}://localhost:${port.port}`) } catch { /* noop */ }
     }
 
-    const isHttpish = port.protocol.toUpperCase() === 'TCP'
+    const isHttpish = baseProtocol(port.protocol) === 'TCP'
 
     const copyToClipboard = (text: string, label: string) => {
         navigator.clipboard.writeText(text).then(
@@ -203,6 +204,16 @@ export function DetailsPanel({ port, onClose, onKill, onCopy }: DetailsPanelProp
                                                 <span className="text-white text-sm">{details.children.length} processes</span>
                                             </div>
                                         )}
+                                        {details.signature && (
+                                            <div className="flex justify-between items-start">
+                                                <span className="text-gray-400 text-sm">Signature</span>
+                                                <span className={`text-sm text-right ${details.signature.verified ? 'text-accent-green' : details.signature.signed ? 'text-accent-yellow' : 'text-gray-400'}`}>
+                                                    {!details.signature.signed
+                                                        ? 'Unsigned'
+                                                        : `${details.signature.publisher ?? 'Unknown publisher'}${details.signature.verified ? '' : ' (not verified)'}`}
+                                                </span>
+                                            </div>
+                                        )}
                                     </>
                                 )}
                                 <div className="flex justify-between items-start">
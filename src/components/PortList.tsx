@@ -30,13 +30,20 @@ function getChangeClass(change?: ChangeState): string {
   }
 }
 
-// 0.0.0.0 / :: bindings are reachable from any network interface, which is a
-// security-relevant signal worth surfacing in the row.
-function isPublicBinding(addr: string): boolean {
-  if (!addr) return false
-  const lower = addr.toLowerCase()
-  return lower.startsWith('0.0.0.0') || lower === '::' || lower.startsWith('::') && !lower.startsWith('::1')
-}
+// Binds reachable beyond this machine are a security-relevant signal worth
+// surfacing in the row; internet-reachable ones are tinted red.
+const EXPOSURE_BADGE = {
+  public: {
+    label: 'Public',
+    className: 'text-accent-red bg-accent-red/10',
+    title: 'reachable from the internet',
+  },
+  lan: {
+    label: 'LAN',
+    className: 'text-accent-yellow bg-accent-yellow/10',
+    title: 'reachable from the local network',
+  },
+} as const
 
 export function PortList({
   ports,
@@ -93,12 +100,12 @@ export function PortList({
                   {changeState === 'new' && (
                     <span className="text-accent-green text-[10px] font-medium">New</span>
                   )}
-                  {isPublicBinding(portInfo.local_address) && (
+                  {portInfo.exposure !== 'local' && (
                     <span
-                      className="text-accent-yellow text-[9px] font-semibold uppercase px-1 py-px bg-accent-yellow/10 rounded tracking-wider"
-                      title={`Bound to ${portInfo.local_address} — reachable from any network interface`}
+                      className={`${EXPOSURE_BADGE[portInfo.exposure].className} text-[9px] font-semibold uppercase px-1 py-px rounded tracking-wider`}
+                      title={`Bound to ${portInfo.local_address} — ${EXPOSURE_BADGE[portInfo.exposure].title}`}
                     >
-                      Public
+                      {EXPOSURE_BADGE[portInfo.exposure].label}
                     </span>
                   )}
                 </div>
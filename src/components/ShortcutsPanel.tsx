@@ -37,6 +37,7 @@ const COMMANDS: { cmd: string; label: string }[] = [
   { cmd: 'refresh', label: 'Refresh the port list now' },
   { cmd: 'export json', label: 'Copy listening ports to clipboard as JSON' },
   { cmd: 'export csv', label: 'Copy listening ports to clipboard as CSV' },
+  { cmd: 'export md', label: 'Copy listening ports to clipboard as a Markdown table' },
   { cmd: 'settings', label: 'Open settings' },
   { cmd: 'clear', label: 'Clear search and selection' },
 ]
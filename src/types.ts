@@ -1,23 +1,128 @@
 export interface PortInfo {
   pid: number;
   port: number;
+  // "TCP", "UDP", or "TCP6" / "UDP6" for IPv6 sockets.
   protocol: string;
   process_name: string;
   process_path: string;
   is_protected: boolean;
   local_address: string;
+  state: string;
+  service_hint: string | null;
+  remote_address: string | null;
+  remote_host: string | null;
+  remote_country: string | null;
+  is_proxied: boolean;
+  proxy_kind: string | null;
+  likely_server: boolean;
+  exposure: 'local' | 'lan' | 'public';
+  // Unix seconds; 0 if unknown. Changes when the listener restarts.
+  process_start_time: number;
+  // TCP queue sizes, when requested and exposed by the OS (Linux). On a
+  // listener recv_queue is the accept backlog, in connections.
+  recv_queue: number | null;
+  send_queue: number | null;
+  // Owner is on the user's favorites list; its rows sort first.
+  is_favorite: boolean;
+}
+
+// Payload of the "ports-diff" event, emitted alongside "ports-updated".
+export interface PortDiff {
+  added: PortInfo[];
+  removed: PortInfo[];
+  changed: PortInfo[];
+}
+
+export interface ProcessGroup {
+  pid: number;
+  process_name: string;
+  process_path: string;
+  is_protected: boolean;
+  ports: PortInfo[];
+}
+
+export interface Settings {
+  refresh_interval_ms: number;
+  default_protocol_filter: string | null;
+  show_protected: boolean;
+  hotkey: string;
+  start_minimized: boolean;
+  minimize_to_tray: boolean;
+}
+
+export interface PortSocket {
+  pid: number | null;
+  process_name: string | null;
+  protocol: string;
+  state: string;
+  local_address: string;
+  remote_address: string | null;
+}
+
+export interface PortDiagnosis {
+  port: number;
+  sockets: PortSocket[];
+  reserved_for: string[];
+  summary: string;
+}
+
+export interface SystemStats {
+  total_memory: number;
+  used_memory: number;
+  cpu_usage_total: number;
+  per_core: number[];
 }
 
 export interface AppState {
   ports: PortInfo[];
   last_updated: number;
   is_admin: boolean;
+  tcp_count: number;
+  udp_count: number;
+  total: number;
+}
+
+// Handed to restart_as_admin and returned by take_restore_view afterwards.
+export interface ViewState {
+  search_query: string;
+  protocol_filter: string | null;
+  selected_port: number | null;
 }
 
 export interface KillResult {
   success: boolean;
   message: string;
   port: number;
+  needs_confirmation: boolean;
+  error: AppError | null;
+  method: string;
+}
+
+// Payload of the "guard-triggered" event.
+export interface GuardTriggered {
+  pid: number;
+  process_name: string;
+  result: KillResult;
+}
+
+// Rejection value of every fallible command.
+export interface AppError {
+  kind: 'AccessDenied' | 'NotFound' | 'Protected' | 'InvalidInput' | 'Io' | 'Other';
+  message: string;
+}
+
+// "TCP6" -> "TCP", for filters that don't care about the address family.
+export function baseProtocol(protocol: string): string {
+  return protocol.toUpperCase().replace(/6$/, '');
+}
+
+export function isAppError(err: unknown): err is AppError {
+  return typeof err === 'object' && err !== null && 'kind' in err && 'message' in err;
+}
+
+export function errorMessage(err: unknown): string {
+  if (isAppError(err) || err instanceof Error) return err.message;
+  return String(err);
 }
 
 export interface CommonPort {
@@ -80,13 +185,46 @@ export function saveCustomPorts(ports: CommonPort[]): void {
 
 export type ChangeState = 'new' | 'removed' | 'stable';
 
+export interface ProcessDetailsLite {
+  pid: number;
+  name: string;
+  path: string;
+}
+
 export interface ProcessDetails {
   pid: number;
   name: string;
   path: string;
   memory_bytes: number;
+  virtual_memory_bytes: number;
+  memory_human: string;
   cpu_percent: number;
   children: number[];
+  cmd: string[];
+  is_suspended: boolean;
+  parent_pid: number | null;
+  parent_name: string | null;
+  disk_read_bytes: number;
+  disk_written_bytes: number;
+  run_duration_secs: number;
+  handle_count: number | null;
+  listening_ports: number[];
+  connections: ConnectionInfo[];
+  signature: SignatureInfo | null;
+}
+
+export interface SignatureInfo {
+  signed: boolean;
+  publisher: string | null;
+  verified: boolean;
+}
+
+export interface ConnectionInfo {
+  protocol: string;
+  local_address: string;
+  local_port: number;
+  remote_address: string | null;
+  state: string;
 }
 
 // Pinned ports — user-favorited port numbers that get sticky-sorted to the top
@@ -111,6 +249,13 @@ export function savePinnedPorts(ports: number[]): void {
   } catch { }
 }
 
+// One line of the backend's in-memory log, returned by get_logs.
+export interface LogEntry {
+  timestamp: number;
+  level: 'info' | 'warn' | 'error';
+  message: string;
+}
+
 // Recently killed processes — capped ring buffer kept in localStorage. Used
 // for the in-app kill history panel. Keep this lean: no sensitive data.
 export interface KillRecord {